@@ -3760,6 +3760,1953 @@ pub mod wasi {
                 }
             }
         }
+
+        #[allow(dead_code, clippy::all)]
+        pub mod udp {
+            #[used]
+            #[doc(hidden)]
+            #[cfg(target_arch = "wasm32")]
+            static __FORCE_SECTION_REF: fn() =
+                super::super::super::__link_custom_section_describing_imports;
+            use super::super::super::_rt;
+            pub type Pollable = super::super::super::wasi::io::poll::Pollable;
+            pub type Network = super::super::super::wasi::sockets::network::Network;
+            pub type ErrorCode = super::super::super::wasi::sockets::network::ErrorCode;
+            pub type IpSocketAddress = super::super::super::wasi::sockets::network::IpSocketAddress;
+            pub type IpAddressFamily = super::super::super::wasi::sockets::network::IpAddressFamily;
+            /// A received datagram.
+            #[derive(Clone)]
+            pub struct IncomingDatagram {
+                /// The payload.
+                ///
+                /// Theoretical max size: ~64 KiB. In practice, typically less than 1500 bytes.
+                pub data: _rt::Vec<u8>,
+                /// The source address.
+                ///
+                /// This field is guaranteed to match the remote address the stream was initialized with, if any.
+                ///
+                /// Equivalent to the `src_addr` out parameter of `recvfrom`.
+                pub remote_address: IpSocketAddress,
+            }
+            impl ::core::fmt::Debug for IncomingDatagram {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.debug_struct("IncomingDatagram")
+                        .field("data", &self.data)
+                        .field("remote-address", &self.remote_address)
+                        .finish()
+                }
+            }
+            /// A datagram to be sent out.
+            #[derive(Clone)]
+            pub struct OutgoingDatagram {
+                /// The payload.
+                pub data: _rt::Vec<u8>,
+                /// The destination address.
+                ///
+                /// The requirements on this field depend on how the stream was initialized:
+                /// - with a remote address: this field must be None or match the stream's remote address exactly.
+                /// - without a remote address: this field is required.
+                ///
+                /// If this value is None, the send operation is equivalent to `send` in POSIX. Otherwise it is equivalent to `sendto`.
+                pub remote_address: Option<IpSocketAddress>,
+            }
+            impl ::core::fmt::Debug for OutgoingDatagram {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.debug_struct("OutgoingDatagram")
+                        .field("data", &self.data)
+                        .field("remote-address", &self.remote_address)
+                        .finish()
+                }
+            }
+            /// A UDP socket handle.
+
+            #[derive(Debug)]
+            #[repr(transparent)]
+            pub struct UdpSocket {
+                handle: _rt::Resource<UdpSocket>,
+            }
+
+            impl UdpSocket {
+                #[doc(hidden)]
+                pub unsafe fn from_handle(handle: u32) -> Self {
+                    Self {
+                        handle: _rt::Resource::from_handle(handle),
+                    }
+                }
+
+                #[doc(hidden)]
+                pub fn take_handle(&self) -> u32 {
+                    _rt::Resource::take_handle(&self.handle)
+                }
+
+                #[doc(hidden)]
+                pub fn handle(&self) -> u32 {
+                    _rt::Resource::handle(&self.handle)
+                }
+            }
+
+            unsafe impl _rt::WasmResource for UdpSocket {
+                #[inline]
+                unsafe fn drop(_handle: u32) {
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unreachable!();
+
+                    #[cfg(target_arch = "wasm32")]
+                    {
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[resource-drop]udp-socket"]
+                            fn drop(_: u32);
+                        }
+
+                        drop(_handle);
+                    }
+                }
+            }
+
+            #[derive(Debug)]
+            #[repr(transparent)]
+            pub struct IncomingDatagramStream {
+                handle: _rt::Resource<IncomingDatagramStream>,
+            }
+
+            impl IncomingDatagramStream {
+                #[doc(hidden)]
+                pub unsafe fn from_handle(handle: u32) -> Self {
+                    Self {
+                        handle: _rt::Resource::from_handle(handle),
+                    }
+                }
+
+                #[doc(hidden)]
+                pub fn take_handle(&self) -> u32 {
+                    _rt::Resource::take_handle(&self.handle)
+                }
+
+                #[doc(hidden)]
+                pub fn handle(&self) -> u32 {
+                    _rt::Resource::handle(&self.handle)
+                }
+            }
+
+            unsafe impl _rt::WasmResource for IncomingDatagramStream {
+                #[inline]
+                unsafe fn drop(_handle: u32) {
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unreachable!();
+
+                    #[cfg(target_arch = "wasm32")]
+                    {
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[resource-drop]incoming-datagram-stream"]
+                            fn drop(_: u32);
+                        }
+
+                        drop(_handle);
+                    }
+                }
+            }
+
+            #[derive(Debug)]
+            #[repr(transparent)]
+            pub struct OutgoingDatagramStream {
+                handle: _rt::Resource<OutgoingDatagramStream>,
+            }
+
+            impl OutgoingDatagramStream {
+                #[doc(hidden)]
+                pub unsafe fn from_handle(handle: u32) -> Self {
+                    Self {
+                        handle: _rt::Resource::from_handle(handle),
+                    }
+                }
+
+                #[doc(hidden)]
+                pub fn take_handle(&self) -> u32 {
+                    _rt::Resource::take_handle(&self.handle)
+                }
+
+                #[doc(hidden)]
+                pub fn handle(&self) -> u32 {
+                    _rt::Resource::handle(&self.handle)
+                }
+            }
+
+            unsafe impl _rt::WasmResource for OutgoingDatagramStream {
+                #[inline]
+                unsafe fn drop(_handle: u32) {
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unreachable!();
+
+                    #[cfg(target_arch = "wasm32")]
+                    {
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[resource-drop]outgoing-datagram-stream"]
+                            fn drop(_: u32);
+                        }
+
+                        drop(_handle);
+                    }
+                }
+            }
+
+            impl UdpSocket {
+                #[allow(unused_unsafe, clippy::all)]
+                /// Bind the socket to a specific network on the provided IP address and port.
+                ///
+                /// If the IP address is zero (`0.0.0.0` in IPv4, `::` in IPv6), it is left to the implementation to decide which
+                /// network interface(s) to bind to.
+                /// If the port is zero, the socket will be bound to a random free port.
+                ///
+                /// # Typical errors
+                /// - `invalid-argument`:          The `local-address` has the wrong address family. (EAFNOSUPPORT, EFAULT on Windows)
+                /// - `invalid-state`:             The socket is already bound. (EINVAL)
+                /// - `address-in-use`:            No ephemeral ports available. (EADDRINUSE, ENOBUFS on Windows)
+                /// - `address-in-use`:            Address is already in use. (EADDRINUSE)
+                /// - `address-not-bindable`:      `local-address` is not an address that the `network` can bind to. (EADDRNOTAVAIL)
+                /// - `not-in-progress`:           A `bind` operation is not in progress.
+                /// - `would-block`:               Can't finish the operation, it is still in progress. (EWOULDBLOCK, EAGAIN)
+                ///
+                /// # Implementors note
+                /// Unlike in POSIX, in WASI the bind operation is async. This enables
+                /// interactive WASI hosts to inject permission prompts. Runtimes that
+                /// don't want to make use of this ability can simply call the native
+                /// `bind` as part of either `start-bind` or `finish-bind`.
+                ///
+                /// # References
+                /// - <https://pubs.opengroup.org/onlinepubs/9699919799/functions/bind.html>
+                /// - <https://man7.org/linux/man-pages/man2/bind.2.html>
+                /// - <https://learn.microsoft.com/en-us/windows/win32/api/winsock/nf-winsock-bind>
+                /// - <https://man.freebsd.org/cgi/man.cgi?query=bind&sektion=2&format=html>
+                pub fn start_bind(
+                    &self,
+                    network: &Network,
+                    local_address: IpSocketAddress,
+                ) -> Result<(), ErrorCode> {
+                    unsafe {
+                        #[repr(align(1))]
+                        struct RetArea([::core::mem::MaybeUninit<u8>; 2]);
+                        let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 2]);
+                        use super::super::super::wasi::sockets::network::IpSocketAddress as V4;
+                        let (
+                            result5_0,
+                            result5_1,
+                            result5_2,
+                            result5_3,
+                            result5_4,
+                            result5_5,
+                            result5_6,
+                            result5_7,
+                            result5_8,
+                            result5_9,
+                            result5_10,
+                            result5_11,
+                        ) = match local_address {
+                            V4::Ipv4(e) => {
+                                let super::super::super::wasi::sockets::network::Ipv4SocketAddress{ port:port0, address:address0, } = e;
+                                let (t1_0, t1_1, t1_2, t1_3) = address0;
+
+                                (
+                                    0i32,
+                                    _rt::as_i32(port0),
+                                    _rt::as_i32(t1_0),
+                                    _rt::as_i32(t1_1),
+                                    _rt::as_i32(t1_2),
+                                    _rt::as_i32(t1_3),
+                                    0i32,
+                                    0i32,
+                                    0i32,
+                                    0i32,
+                                    0i32,
+                                    0i32,
+                                )
+                            }
+                            V4::Ipv6(e) => {
+                                let super::super::super::wasi::sockets::network::Ipv6SocketAddress{ port:port2, flow_info:flow_info2, address:address2, scope_id:scope_id2, } = e;
+                                let (t3_0, t3_1, t3_2, t3_3, t3_4, t3_5, t3_6, t3_7) = address2;
+
+                                (
+                                    1i32,
+                                    _rt::as_i32(port2),
+                                    _rt::as_i32(flow_info2),
+                                    _rt::as_i32(t3_0),
+                                    _rt::as_i32(t3_1),
+                                    _rt::as_i32(t3_2),
+                                    _rt::as_i32(t3_3),
+                                    _rt::as_i32(t3_4),
+                                    _rt::as_i32(t3_5),
+                                    _rt::as_i32(t3_6),
+                                    _rt::as_i32(t3_7),
+                                    _rt::as_i32(scope_id2),
+                                )
+                            }
+                        };
+                        let ptr6 = ret_area.0.as_mut_ptr().cast::<u8>();
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]udp-socket.start-bind"]
+                            fn wit_import(
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: *mut u8,
+                            );
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: *mut u8,
+                        ) {
+                            unreachable!()
+                        }
+                        wit_import(
+                            (self).handle() as i32,
+                            (network).handle() as i32,
+                            result5_0,
+                            result5_1,
+                            result5_2,
+                            result5_3,
+                            result5_4,
+                            result5_5,
+                            result5_6,
+                            result5_7,
+                            result5_8,
+                            result5_9,
+                            result5_10,
+                            result5_11,
+                            ptr6,
+                        );
+                        let l7 = i32::from(*ptr6.add(0).cast::<u8>());
+                        match l7 {
+                            0 => {
+                                let e = ();
+                                Ok(e)
+                            }
+                            1 => {
+                                let e = {
+                                    let l8 = i32::from(*ptr6.add(1).cast::<u8>());
+
+                                    super::super::super::wasi::sockets::network::ErrorCode::_lift(
+                                        l8 as u8,
+                                    )
+                                };
+                                Err(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
+                        }
+                    }
+                }
+            }
+            impl UdpSocket {
+                #[allow(unused_unsafe, clippy::all)]
+                pub fn finish_bind(&self) -> Result<(), ErrorCode> {
+                    unsafe {
+                        #[repr(align(1))]
+                        struct RetArea([::core::mem::MaybeUninit<u8>; 2]);
+                        let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 2]);
+                        let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]udp-socket.finish-bind"]
+                            fn wit_import(_: i32, _: *mut u8);
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(_: i32, _: *mut u8) {
+                            unreachable!()
+                        }
+                        wit_import((self).handle() as i32, ptr0);
+                        let l1 = i32::from(*ptr0.add(0).cast::<u8>());
+                        match l1 {
+                            0 => {
+                                let e = ();
+                                Ok(e)
+                            }
+                            1 => {
+                                let e = {
+                                    let l2 = i32::from(*ptr0.add(1).cast::<u8>());
+
+                                    super::super::super::wasi::sockets::network::ErrorCode::_lift(
+                                        l2 as u8,
+                                    )
+                                };
+                                Err(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
+                        }
+                    }
+                }
+            }
+            impl UdpSocket {
+                #[allow(unused_unsafe, clippy::all)]
+                /// Set up inbound & outbound communication channels, optionally to a specific peer.
+                ///
+                /// This function only changes the local socket configuration and does not generate any network traffic.
+                /// On success, the `remote-address` of the socket is updated. The `local-address` may be updated as well,
+                /// based on the best network path to `remote-address`.
+                ///
+                /// When a `remote-address` is provided, the returned streams are limited to communicating with that specific peer:
+                /// - `send` can only be used to send to this destination.
+                /// - `receive` will only return datagrams sent from the provided `remote-address`.
+                ///
+                /// This method may be called multiple times on the same socket to change its association, but
+                /// only the most recently returned pair of streams will be operational. Implementations may trap if
+                /// the streams returned by a previous invocation haven't been dropped yet before calling `stream` again.
+                ///
+                /// The POSIX equivalent in pseudo-code is:
+                /// ```text
+                /// if (was previously connected) {
+                /// connect(s, AF_UNSPEC)
+                /// }
+                /// if (remote_address is Some) {
+                /// connect(s, remote_address)
+                /// }
+                /// ```
+                ///
+                /// Unlike in POSIX, the socket must already be explicitly bound.
+                ///
+                /// # Typical errors
+                /// - `invalid-argument`:          The `remote-address` has the wrong address family. (EAFNOSUPPORT)
+                /// - `invalid-argument`:          The IP address in `remote-address` is set to INADDR_ANY (`0.0.0.0` / `::`). (EDESTADDRREQ, EADDRNOTAVAIL)
+                /// - `invalid-argument`:          The port in `remote-address` is set to 0. (EDESTADDRREQ, EADDRNOTAVAIL)
+                /// - `invalid-state`:             The socket is not bound.
+                /// - `address-in-use`:            Tried to perform an implicit bind, but there were no ephemeral ports available. (EADDRINUSE, EADDRNOTAVAIL on Linux, EAGAIN on BSD)
+                /// - `remote-unreachable`:        The remote address is not reachable. (ECONNRESET, ENETRESET, EHOSTUNREACH, EHOSTDOWN, ENETUNREACH, ENETDOWN, ENONET)
+                /// - `connection-refused`:        The connection was refused. (ECONNREFUSED)
+                ///
+                /// # References
+                /// - <https://pubs.opengroup.org/onlinepubs/9699919799/functions/connect.html>
+                /// - <https://man7.org/linux/man-pages/man2/connect.2.html>
+                /// - <https://learn.microsoft.com/en-us/windows/win32/api/winsock2/nf-winsock2-connect>
+                /// - <https://man.freebsd.org/cgi/man.cgi?connect>
+                pub fn stream(
+                    &self,
+                    remote_address: Option<IpSocketAddress>,
+                ) -> Result<(IncomingDatagramStream, OutgoingDatagramStream), ErrorCode>
+                {
+                    unsafe {
+                        #[repr(align(4))]
+                        struct RetArea([::core::mem::MaybeUninit<u8>; 12]);
+                        let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 12]);
+                        let (
+                            result6_0,
+                            result6_1,
+                            result6_2,
+                            result6_3,
+                            result6_4,
+                            result6_5,
+                            result6_6,
+                            result6_7,
+                            result6_8,
+                            result6_9,
+                            result6_10,
+                            result6_11,
+                            result6_12,
+                        ) = match remote_address {
+                            Some(e) => {
+                                use super::super::super::wasi::sockets::network::IpSocketAddress as V4;
+                                let (
+                                    result5_0,
+                                    result5_1,
+                                    result5_2,
+                                    result5_3,
+                                    result5_4,
+                                    result5_5,
+                                    result5_6,
+                                    result5_7,
+                                    result5_8,
+                                    result5_9,
+                                    result5_10,
+                                    result5_11,
+                                ) = match e {
+                                    V4::Ipv4(e) => {
+                                        let super::super::super::wasi::sockets::network::Ipv4SocketAddress{ port:port0, address:address0, } = e;
+                                        let (t1_0, t1_1, t1_2, t1_3) = address0;
+
+                                        (
+                                            0i32,
+                                            _rt::as_i32(port0),
+                                            _rt::as_i32(t1_0),
+                                            _rt::as_i32(t1_1),
+                                            _rt::as_i32(t1_2),
+                                            _rt::as_i32(t1_3),
+                                            0i32,
+                                            0i32,
+                                            0i32,
+                                            0i32,
+                                            0i32,
+                                            0i32,
+                                        )
+                                    }
+                                    V4::Ipv6(e) => {
+                                        let super::super::super::wasi::sockets::network::Ipv6SocketAddress{ port:port2, flow_info:flow_info2, address:address2, scope_id:scope_id2, } = e;
+                                        let (t3_0, t3_1, t3_2, t3_3, t3_4, t3_5, t3_6, t3_7) =
+                                            address2;
+
+                                        (
+                                            1i32,
+                                            _rt::as_i32(port2),
+                                            _rt::as_i32(flow_info2),
+                                            _rt::as_i32(t3_0),
+                                            _rt::as_i32(t3_1),
+                                            _rt::as_i32(t3_2),
+                                            _rt::as_i32(t3_3),
+                                            _rt::as_i32(t3_4),
+                                            _rt::as_i32(t3_5),
+                                            _rt::as_i32(t3_6),
+                                            _rt::as_i32(t3_7),
+                                            _rt::as_i32(scope_id2),
+                                        )
+                                    }
+                                };
+
+                                (
+                                    1i32, result5_0, result5_1, result5_2, result5_3, result5_4,
+                                    result5_5, result5_6, result5_7, result5_8, result5_9,
+                                    result5_10, result5_11,
+                                )
+                            }
+                            None => (
+                                0i32, 0i32, 0i32, 0i32, 0i32, 0i32, 0i32, 0i32, 0i32, 0i32, 0i32,
+                                0i32, 0i32,
+                            ),
+                        };
+                        let ptr7 = ret_area.0.as_mut_ptr().cast::<u8>();
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]udp-socket.stream"]
+                            fn wit_import(
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: i32,
+                                _: *mut u8,
+                            );
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: i32,
+                            _: *mut u8,
+                        ) {
+                            unreachable!()
+                        }
+                        wit_import(
+                            (self).handle() as i32,
+                            result6_0,
+                            result6_1,
+                            result6_2,
+                            result6_3,
+                            result6_4,
+                            result6_5,
+                            result6_6,
+                            result6_7,
+                            result6_8,
+                            result6_9,
+                            result6_10,
+                            result6_11,
+                            result6_12,
+                            ptr7,
+                        );
+                        let l8 = i32::from(*ptr7.add(0).cast::<u8>());
+                        match l8 {
+                            0 => {
+                                let e = {
+                                    let l9 = *ptr7.add(4).cast::<i32>();
+                                    let l10 = *ptr7.add(8).cast::<i32>();
+
+                                    (
+                                        IncomingDatagramStream::from_handle(l9 as u32),
+                                        OutgoingDatagramStream::from_handle(l10 as u32),
+                                    )
+                                };
+                                Ok(e)
+                            }
+                            1 => {
+                                let e = {
+                                    let l11 = i32::from(*ptr7.add(4).cast::<u8>());
+
+                                    super::super::super::wasi::sockets::network::ErrorCode::_lift(
+                                        l11 as u8,
+                                    )
+                                };
+                                Err(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
+                        }
+                    }
+                }
+            }
+            impl UdpSocket {
+                #[allow(unused_unsafe, clippy::all)]
+                /// Get the current bound address.
+                ///
+                /// POSIX mentions:
+                /// > If the socket has not been bound to a local name, the value
+                /// > stored in the object pointed to by `address` is unspecified.
+                ///
+                /// WASI is stricter and requires `local-address` to return `invalid-state` when the socket hasn't been bound yet.
+                ///
+                /// # Typical errors
+                /// - `invalid-state`: The socket is not bound to any local address.
+                ///
+                /// # References
+                /// - <https://pubs.opengroup.org/onlinepubs/9699919799/functions/getsockname.html>
+                /// - <https://man7.org/linux/man-pages/man2/getsockname.2.html>
+                /// - <https://learn.microsoft.com/en-us/windows/win32/api/winsock/nf-winsock-getsockname>
+                /// - <https://man.freebsd.org/cgi/man.cgi?getsockname>
+                pub fn local_address(&self) -> Result<IpSocketAddress, ErrorCode> {
+                    unsafe {
+                        #[repr(align(4))]
+                        struct RetArea([::core::mem::MaybeUninit<u8>; 36]);
+                        let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 36]);
+                        let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]udp-socket.local-address"]
+                            fn wit_import(_: i32, _: *mut u8);
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(_: i32, _: *mut u8) {
+                            unreachable!()
+                        }
+                        wit_import((self).handle() as i32, ptr0);
+                        let l1 = i32::from(*ptr0.add(0).cast::<u8>());
+                        match l1 {
+                            0 => {
+                                let e = {
+                                    let l2 = i32::from(*ptr0.add(4).cast::<u8>());
+                                    use super::super::super::wasi::sockets::network::IpSocketAddress as V19;
+                                    let v19 = match l2 {
+                                        0 => {
+                                            let e19 = {
+                                                let l3 = i32::from(*ptr0.add(8).cast::<u16>());
+                                                let l4 = i32::from(*ptr0.add(10).cast::<u8>());
+                                                let l5 = i32::from(*ptr0.add(11).cast::<u8>());
+                                                let l6 = i32::from(*ptr0.add(12).cast::<u8>());
+                                                let l7 = i32::from(*ptr0.add(13).cast::<u8>());
+
+                                                super::super::super::wasi::sockets::network::Ipv4SocketAddress{
+                          port: l3 as u16,
+                          address: (l4 as u8, l5 as u8, l6 as u8, l7 as u8),
+                        }
+                                            };
+                                            V19::Ipv4(e19)
+                                        }
+                                        n => {
+                                            debug_assert_eq!(n, 1, "invalid enum discriminant");
+                                            let e19 = {
+                                                let l8 = i32::from(*ptr0.add(8).cast::<u16>());
+                                                let l9 = *ptr0.add(12).cast::<i32>();
+                                                let l10 = i32::from(*ptr0.add(16).cast::<u16>());
+                                                let l11 = i32::from(*ptr0.add(18).cast::<u16>());
+                                                let l12 = i32::from(*ptr0.add(20).cast::<u16>());
+                                                let l13 = i32::from(*ptr0.add(22).cast::<u16>());
+                                                let l14 = i32::from(*ptr0.add(24).cast::<u16>());
+                                                let l15 = i32::from(*ptr0.add(26).cast::<u16>());
+                                                let l16 = i32::from(*ptr0.add(28).cast::<u16>());
+                                                let l17 = i32::from(*ptr0.add(30).cast::<u16>());
+                                                let l18 = *ptr0.add(32).cast::<i32>();
+
+                                                super::super::super::wasi::sockets::network::Ipv6SocketAddress{
+                          port: l8 as u16,
+                          flow_info: l9 as u32,
+                          address: (l10 as u16, l11 as u16, l12 as u16, l13 as u16, l14 as u16, l15 as u16, l16 as u16, l17 as u16),
+                          scope_id: l18 as u32,
+                        }
+                                            };
+                                            V19::Ipv6(e19)
+                                        }
+                                    };
+
+                                    v19
+                                };
+                                Ok(e)
+                            }
+                            1 => {
+                                let e = {
+                                    let l20 = i32::from(*ptr0.add(4).cast::<u8>());
+
+                                    super::super::super::wasi::sockets::network::ErrorCode::_lift(
+                                        l20 as u8,
+                                    )
+                                };
+                                Err(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
+                        }
+                    }
+                }
+            }
+            impl UdpSocket {
+                #[allow(unused_unsafe, clippy::all)]
+                /// Get the address the socket is currently streaming to.
+                ///
+                /// # Typical errors
+                /// - `invalid-state`: The socket is not streaming to a specific remote address. (ENOTCONN)
+                ///
+                /// # References
+                /// - <https://pubs.opengroup.org/onlinepubs/9699919799/functions/getpeername.html>
+                /// - <https://man7.org/linux/man-pages/man2/getpeername.2.html>
+                /// - <https://learn.microsoft.com/en-us/windows/win32/api/winsock/nf-winsock-getpeername>
+                /// - <https://man.freebsd.org/cgi/man.cgi?query=getpeername&sektion=2&n=1>
+                pub fn remote_address(&self) -> Result<IpSocketAddress, ErrorCode> {
+                    unsafe {
+                        #[repr(align(4))]
+                        struct RetArea([::core::mem::MaybeUninit<u8>; 36]);
+                        let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 36]);
+                        let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]udp-socket.remote-address"]
+                            fn wit_import(_: i32, _: *mut u8);
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(_: i32, _: *mut u8) {
+                            unreachable!()
+                        }
+                        wit_import((self).handle() as i32, ptr0);
+                        let l1 = i32::from(*ptr0.add(0).cast::<u8>());
+                        match l1 {
+                            0 => {
+                                let e = {
+                                    let l2 = i32::from(*ptr0.add(4).cast::<u8>());
+                                    use super::super::super::wasi::sockets::network::IpSocketAddress as V19;
+                                    let v19 = match l2 {
+                                        0 => {
+                                            let e19 = {
+                                                let l3 = i32::from(*ptr0.add(8).cast::<u16>());
+                                                let l4 = i32::from(*ptr0.add(10).cast::<u8>());
+                                                let l5 = i32::from(*ptr0.add(11).cast::<u8>());
+                                                let l6 = i32::from(*ptr0.add(12).cast::<u8>());
+                                                let l7 = i32::from(*ptr0.add(13).cast::<u8>());
+
+                                                super::super::super::wasi::sockets::network::Ipv4SocketAddress{
+                          port: l3 as u16,
+                          address: (l4 as u8, l5 as u8, l6 as u8, l7 as u8),
+                        }
+                                            };
+                                            V19::Ipv4(e19)
+                                        }
+                                        n => {
+                                            debug_assert_eq!(n, 1, "invalid enum discriminant");
+                                            let e19 = {
+                                                let l8 = i32::from(*ptr0.add(8).cast::<u16>());
+                                                let l9 = *ptr0.add(12).cast::<i32>();
+                                                let l10 = i32::from(*ptr0.add(16).cast::<u16>());
+                                                let l11 = i32::from(*ptr0.add(18).cast::<u16>());
+                                                let l12 = i32::from(*ptr0.add(20).cast::<u16>());
+                                                let l13 = i32::from(*ptr0.add(22).cast::<u16>());
+                                                let l14 = i32::from(*ptr0.add(24).cast::<u16>());
+                                                let l15 = i32::from(*ptr0.add(26).cast::<u16>());
+                                                let l16 = i32::from(*ptr0.add(28).cast::<u16>());
+                                                let l17 = i32::from(*ptr0.add(30).cast::<u16>());
+                                                let l18 = *ptr0.add(32).cast::<i32>();
+
+                                                super::super::super::wasi::sockets::network::Ipv6SocketAddress{
+                          port: l8 as u16,
+                          flow_info: l9 as u32,
+                          address: (l10 as u16, l11 as u16, l12 as u16, l13 as u16, l14 as u16, l15 as u16, l16 as u16, l17 as u16),
+                          scope_id: l18 as u32,
+                        }
+                                            };
+                                            V19::Ipv6(e19)
+                                        }
+                                    };
+
+                                    v19
+                                };
+                                Ok(e)
+                            }
+                            1 => {
+                                let e = {
+                                    let l20 = i32::from(*ptr0.add(4).cast::<u8>());
+
+                                    super::super::super::wasi::sockets::network::ErrorCode::_lift(
+                                        l20 as u8,
+                                    )
+                                };
+                                Err(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
+                        }
+                    }
+                }
+            }
+            impl UdpSocket {
+                #[allow(unused_unsafe, clippy::all)]
+                /// Whether this is a IPv4 or IPv6 socket.
+                ///
+                /// Equivalent to the SO_DOMAIN socket option.
+                pub fn address_family(&self) -> IpAddressFamily {
+                    unsafe {
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]udp-socket.address-family"]
+                            fn wit_import(_: i32) -> i32;
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(_: i32) -> i32 {
+                            unreachable!()
+                        }
+                        let ret = wit_import((self).handle() as i32);
+                        super::super::super::wasi::sockets::network::IpAddressFamily::_lift(
+                            ret as u8,
+                        )
+                    }
+                }
+            }
+            impl UdpSocket {
+                #[allow(unused_unsafe, clippy::all)]
+                /// Equivalent to the IP_TTL & IPV6_UNICAST_HOPS socket options.
+                ///
+                /// If the provided value is 0, an `invalid-argument` error is returned.
+                ///
+                /// # Typical errors
+                /// - `invalid-argument`:     (set) The TTL value must be 1 or higher.
+                pub fn unicast_hop_limit(&self) -> Result<u8, ErrorCode> {
+                    unsafe {
+                        #[repr(align(1))]
+                        struct RetArea([::core::mem::MaybeUninit<u8>; 2]);
+                        let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 2]);
+                        let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]udp-socket.unicast-hop-limit"]
+                            fn wit_import(_: i32, _: *mut u8);
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(_: i32, _: *mut u8) {
+                            unreachable!()
+                        }
+                        wit_import((self).handle() as i32, ptr0);
+                        let l1 = i32::from(*ptr0.add(0).cast::<u8>());
+                        match l1 {
+                            0 => {
+                                let e = {
+                                    let l2 = i32::from(*ptr0.add(1).cast::<u8>());
+
+                                    l2 as u8
+                                };
+                                Ok(e)
+                            }
+                            1 => {
+                                let e = {
+                                    let l3 = i32::from(*ptr0.add(1).cast::<u8>());
+
+                                    super::super::super::wasi::sockets::network::ErrorCode::_lift(
+                                        l3 as u8,
+                                    )
+                                };
+                                Err(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
+                        }
+                    }
+                }
+            }
+            impl UdpSocket {
+                #[allow(unused_unsafe, clippy::all)]
+                pub fn set_unicast_hop_limit(&self, value: u8) -> Result<(), ErrorCode> {
+                    unsafe {
+                        #[repr(align(1))]
+                        struct RetArea([::core::mem::MaybeUninit<u8>; 2]);
+                        let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 2]);
+                        let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]udp-socket.set-unicast-hop-limit"]
+                            fn wit_import(_: i32, _: i32, _: *mut u8);
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(_: i32, _: i32, _: *mut u8) {
+                            unreachable!()
+                        }
+                        wit_import((self).handle() as i32, _rt::as_i32(&value), ptr0);
+                        let l1 = i32::from(*ptr0.add(0).cast::<u8>());
+                        match l1 {
+                            0 => {
+                                let e = ();
+                                Ok(e)
+                            }
+                            1 => {
+                                let e = {
+                                    let l2 = i32::from(*ptr0.add(1).cast::<u8>());
+
+                                    super::super::super::wasi::sockets::network::ErrorCode::_lift(
+                                        l2 as u8,
+                                    )
+                                };
+                                Err(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
+                        }
+                    }
+                }
+            }
+            impl UdpSocket {
+                #[allow(unused_unsafe, clippy::all)]
+                /// The kernel buffer space reserved for sends/receives on this socket.
+                ///
+                /// If the provided value is 0, an `invalid-argument` error is returned.
+                /// Any other value will never cause an error, but it might be silently clamped and/or rounded.
+                /// I.e. after setting a value, reading the same setting back may return a different value.
+                ///
+                /// Equivalent to the SO_RCVBUF and SO_SNDBUF socket options.
+                ///
+                /// # Typical errors
+                /// - `invalid-argument`:     (set) The provided value was 0.
+                pub fn receive_buffer_size(&self) -> Result<u64, ErrorCode> {
+                    unsafe {
+                        #[repr(align(8))]
+                        struct RetArea([::core::mem::MaybeUninit<u8>; 16]);
+                        let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 16]);
+                        let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]udp-socket.receive-buffer-size"]
+                            fn wit_import(_: i32, _: *mut u8);
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(_: i32, _: *mut u8) {
+                            unreachable!()
+                        }
+                        wit_import((self).handle() as i32, ptr0);
+                        let l1 = i32::from(*ptr0.add(0).cast::<u8>());
+                        match l1 {
+                            0 => {
+                                let e = {
+                                    let l2 = *ptr0.add(8).cast::<i64>();
+
+                                    l2 as u64
+                                };
+                                Ok(e)
+                            }
+                            1 => {
+                                let e = {
+                                    let l3 = i32::from(*ptr0.add(8).cast::<u8>());
+
+                                    super::super::super::wasi::sockets::network::ErrorCode::_lift(
+                                        l3 as u8,
+                                    )
+                                };
+                                Err(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
+                        }
+                    }
+                }
+            }
+            impl UdpSocket {
+                #[allow(unused_unsafe, clippy::all)]
+                pub fn set_receive_buffer_size(&self, value: u64) -> Result<(), ErrorCode> {
+                    unsafe {
+                        #[repr(align(1))]
+                        struct RetArea([::core::mem::MaybeUninit<u8>; 2]);
+                        let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 2]);
+                        let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]udp-socket.set-receive-buffer-size"]
+                            fn wit_import(_: i32, _: i64, _: *mut u8);
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(_: i32, _: i64, _: *mut u8) {
+                            unreachable!()
+                        }
+                        wit_import((self).handle() as i32, _rt::as_i64(&value), ptr0);
+                        let l1 = i32::from(*ptr0.add(0).cast::<u8>());
+                        match l1 {
+                            0 => {
+                                let e = ();
+                                Ok(e)
+                            }
+                            1 => {
+                                let e = {
+                                    let l2 = i32::from(*ptr0.add(1).cast::<u8>());
+
+                                    super::super::super::wasi::sockets::network::ErrorCode::_lift(
+                                        l2 as u8,
+                                    )
+                                };
+                                Err(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
+                        }
+                    }
+                }
+            }
+            impl UdpSocket {
+                #[allow(unused_unsafe, clippy::all)]
+                pub fn send_buffer_size(&self) -> Result<u64, ErrorCode> {
+                    unsafe {
+                        #[repr(align(8))]
+                        struct RetArea([::core::mem::MaybeUninit<u8>; 16]);
+                        let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 16]);
+                        let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]udp-socket.send-buffer-size"]
+                            fn wit_import(_: i32, _: *mut u8);
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(_: i32, _: *mut u8) {
+                            unreachable!()
+                        }
+                        wit_import((self).handle() as i32, ptr0);
+                        let l1 = i32::from(*ptr0.add(0).cast::<u8>());
+                        match l1 {
+                            0 => {
+                                let e = {
+                                    let l2 = *ptr0.add(8).cast::<i64>();
+
+                                    l2 as u64
+                                };
+                                Ok(e)
+                            }
+                            1 => {
+                                let e = {
+                                    let l3 = i32::from(*ptr0.add(8).cast::<u8>());
+
+                                    super::super::super::wasi::sockets::network::ErrorCode::_lift(
+                                        l3 as u8,
+                                    )
+                                };
+                                Err(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
+                        }
+                    }
+                }
+            }
+            impl UdpSocket {
+                #[allow(unused_unsafe, clippy::all)]
+                pub fn set_send_buffer_size(&self, value: u64) -> Result<(), ErrorCode> {
+                    unsafe {
+                        #[repr(align(1))]
+                        struct RetArea([::core::mem::MaybeUninit<u8>; 2]);
+                        let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 2]);
+                        let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]udp-socket.set-send-buffer-size"]
+                            fn wit_import(_: i32, _: i64, _: *mut u8);
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(_: i32, _: i64, _: *mut u8) {
+                            unreachable!()
+                        }
+                        wit_import((self).handle() as i32, _rt::as_i64(&value), ptr0);
+                        let l1 = i32::from(*ptr0.add(0).cast::<u8>());
+                        match l1 {
+                            0 => {
+                                let e = ();
+                                Ok(e)
+                            }
+                            1 => {
+                                let e = {
+                                    let l2 = i32::from(*ptr0.add(1).cast::<u8>());
+
+                                    super::super::super::wasi::sockets::network::ErrorCode::_lift(
+                                        l2 as u8,
+                                    )
+                                };
+                                Err(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
+                        }
+                    }
+                }
+            }
+            impl UdpSocket {
+                #[allow(unused_unsafe, clippy::all)]
+                /// Create a `pollable` which will resolve once the socket is ready for I/O.
+                ///
+                /// Note: this function is here for WASI Preview2 only.
+                /// It's planned to be removed when `future` is natively supported in Preview3.
+                pub fn subscribe(&self) -> Pollable {
+                    unsafe {
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]udp-socket.subscribe"]
+                            fn wit_import(_: i32) -> i32;
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(_: i32) -> i32 {
+                            unreachable!()
+                        }
+                        let ret = wit_import((self).handle() as i32);
+                        super::super::super::wasi::io::poll::Pollable::from_handle(ret as u32)
+                    }
+                }
+            }
+            impl IncomingDatagramStream {
+                #[allow(unused_unsafe, clippy::all)]
+                /// Receive messages on the socket.
+                ///
+                /// This function attempts to receive up to `max-results` datagrams on the socket without blocking.
+                /// The returned list may contain fewer elements than requested, but never more.
+                ///
+                /// This function returns successfully with an empty list when either:
+                /// - `max-results` is 0, or:
+                /// - `max-results` is greater than 0, but no results are immediately available.
+                /// This function never returns `error(would-block)`.
+                ///
+                /// # Typical errors
+                /// - `remote-unreachable`: The remote address is not reachable. (ECONNRESET, ENETRESET on Windows, EHOSTUNREACH, EHOSTDOWN, ENETUNREACH, ENETDOWN, ENONET)
+                /// - `connection-refused`: The connection was refused. (ECONNREFUSED)
+                ///
+                /// # References
+                /// - <https://pubs.opengroup.org/onlinepubs/9699919799/functions/recvfrom.html>
+                /// - <https://pubs.opengroup.org/onlinepubs/9699919799/functions/recvmsg.html>
+                /// - <https://man7.org/linux/man-pages/man2/recv.2.html>
+                /// - <https://man7.org/linux/man-pages/man2/recvmmsg.2.html>
+                /// - <https://learn.microsoft.com/en-us/windows/win32/api/winsock/nf-winsock-recv>
+                /// - <https://learn.microsoft.com/en-us/windows/win32/api/winsock/nf-winsock-recvfrom>
+                /// - <https://learn.microsoft.com/en-us/previous-versions/windows/desktop/legacy/ms741687(v=vs.85)>
+                /// - <https://man.freebsd.org/cgi/man.cgi?query=recv&sektion=2>
+                pub fn receive(
+                    &self,
+                    max_results: u64,
+                ) -> Result<_rt::Vec<IncomingDatagram>, ErrorCode> {
+                    unsafe {
+                        #[repr(align(4))]
+                        struct RetArea([::core::mem::MaybeUninit<u8>; 12]);
+                        let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 12]);
+                        let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]incoming-datagram-stream.receive"]
+                            fn wit_import(_: i32, _: i64, _: *mut u8);
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(_: i32, _: i64, _: *mut u8) {
+                            unreachable!()
+                        }
+                        wit_import((self).handle() as i32, _rt::as_i64(&max_results), ptr0);
+                        let l1 = i32::from(*ptr0.add(0).cast::<u8>());
+                        match l1 {
+                            0 => {
+                                let e = {
+                                    let l2 = *ptr0.add(4).cast::<*mut u8>();
+                                    let l3 = *ptr0.add(8).cast::<usize>();
+                                    let base25 = l2;
+                                    let len25 = l3;
+                                    let mut result25 = _rt::Vec::with_capacity(len25);
+                                    for i in 0..len25 {
+                                        let base = base25.add(i * 40);
+                                        let e25 = {
+                                            let l4 = *base.add(0).cast::<*mut u8>();
+                                            let l5 = *base.add(4).cast::<usize>();
+                                            let len6 = l5;
+                                            let l7 = i32::from(*base.add(8).cast::<u8>());
+                                            use super::super::super::wasi::sockets::network::IpSocketAddress as V24;
+                                            let v24 = match l7 {
+                                                0 => {
+                                                    let e24 = {
+                                                        let l8 =
+                                                            i32::from(*base.add(12).cast::<u16>());
+                                                        let l9 =
+                                                            i32::from(*base.add(14).cast::<u8>());
+                                                        let l10 =
+                                                            i32::from(*base.add(15).cast::<u8>());
+                                                        let l11 =
+                                                            i32::from(*base.add(16).cast::<u8>());
+                                                        let l12 =
+                                                            i32::from(*base.add(17).cast::<u8>());
+
+                                                        super::super::super::wasi::sockets::network::Ipv4SocketAddress{
+                              port: l8 as u16,
+                              address: (l9 as u8, l10 as u8, l11 as u8, l12 as u8),
+                            }
+                                                    };
+                                                    V24::Ipv4(e24)
+                                                }
+                                                n => {
+                                                    debug_assert_eq!(
+                                                        n, 1,
+                                                        "invalid enum discriminant"
+                                                    );
+                                                    let e24 = {
+                                                        let l13 =
+                                                            i32::from(*base.add(12).cast::<u16>());
+                                                        let l14 = *base.add(16).cast::<i32>();
+                                                        let l15 =
+                                                            i32::from(*base.add(20).cast::<u16>());
+                                                        let l16 =
+                                                            i32::from(*base.add(22).cast::<u16>());
+                                                        let l17 =
+                                                            i32::from(*base.add(24).cast::<u16>());
+                                                        let l18 =
+                                                            i32::from(*base.add(26).cast::<u16>());
+                                                        let l19 =
+                                                            i32::from(*base.add(28).cast::<u16>());
+                                                        let l20 =
+                                                            i32::from(*base.add(30).cast::<u16>());
+                                                        let l21 =
+                                                            i32::from(*base.add(32).cast::<u16>());
+                                                        let l22 =
+                                                            i32::from(*base.add(34).cast::<u16>());
+                                                        let l23 = *base.add(36).cast::<i32>();
+
+                                                        super::super::super::wasi::sockets::network::Ipv6SocketAddress{
+                              port: l13 as u16,
+                              flow_info: l14 as u32,
+                              address: (l15 as u16, l16 as u16, l17 as u16, l18 as u16, l19 as u16, l20 as u16, l21 as u16, l22 as u16),
+                              scope_id: l23 as u32,
+                            }
+                                                    };
+                                                    V24::Ipv6(e24)
+                                                }
+                                            };
+
+                                            IncomingDatagram {
+                                                data: _rt::Vec::from_raw_parts(
+                                                    l4.cast(),
+                                                    len6,
+                                                    len6,
+                                                ),
+                                                remote_address: v24,
+                                            }
+                                        };
+                                        result25.push(e25);
+                                    }
+                                    _rt::cabi_dealloc(base25, len25 * 40, 4);
+
+                                    result25
+                                };
+                                Ok(e)
+                            }
+                            1 => {
+                                let e = {
+                                    let l26 = i32::from(*ptr0.add(4).cast::<u8>());
+
+                                    super::super::super::wasi::sockets::network::ErrorCode::_lift(
+                                        l26 as u8,
+                                    )
+                                };
+                                Err(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
+                        }
+                    }
+                }
+            }
+            impl IncomingDatagramStream {
+                #[allow(unused_unsafe, clippy::all)]
+                /// Create a `pollable` which will resolve once the stream is ready to receive again.
+                ///
+                /// Note: this function is here for WASI Preview2 only.
+                /// It's planned to be removed when `future` is natively supported in Preview3.
+                pub fn subscribe(&self) -> Pollable {
+                    unsafe {
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]incoming-datagram-stream.subscribe"]
+                            fn wit_import(_: i32) -> i32;
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(_: i32) -> i32 {
+                            unreachable!()
+                        }
+                        let ret = wit_import((self).handle() as i32);
+                        super::super::super::wasi::io::poll::Pollable::from_handle(ret as u32)
+                    }
+                }
+            }
+            impl OutgoingDatagramStream {
+                #[allow(unused_unsafe, clippy::all)]
+                /// Check readiness for sending. This function never blocks.
+                ///
+                /// Returns the number of datagrams permitted for the next call to `send`,
+                /// or an error. Calling `send` with more datagrams than this function has
+                /// permitted will trap.
+                ///
+                /// When this function returns ok(0), the `subscribe` pollable will
+                /// become ready when this function will report at least ok(1), or an
+                /// error.
+                ///
+                /// Never returns `would-block`.
+                pub fn check_send(&self) -> Result<u64, ErrorCode> {
+                    unsafe {
+                        #[repr(align(8))]
+                        struct RetArea([::core::mem::MaybeUninit<u8>; 16]);
+                        let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 16]);
+                        let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]outgoing-datagram-stream.check-send"]
+                            fn wit_import(_: i32, _: *mut u8);
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(_: i32, _: *mut u8) {
+                            unreachable!()
+                        }
+                        wit_import((self).handle() as i32, ptr0);
+                        let l1 = i32::from(*ptr0.add(0).cast::<u8>());
+                        match l1 {
+                            0 => {
+                                let e = {
+                                    let l2 = *ptr0.add(8).cast::<i64>();
+
+                                    l2 as u64
+                                };
+                                Ok(e)
+                            }
+                            1 => {
+                                let e = {
+                                    let l3 = i32::from(*ptr0.add(8).cast::<u8>());
+
+                                    super::super::super::wasi::sockets::network::ErrorCode::_lift(
+                                        l3 as u8,
+                                    )
+                                };
+                                Err(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
+                        }
+                    }
+                }
+            }
+            impl OutgoingDatagramStream {
+                #[allow(unused_unsafe, clippy::all)]
+                /// Send messages on the socket.
+                ///
+                /// This function attempts to send all provided `datagrams` on the socket without blocking and
+                /// returns how many messages were actually sent (or queued for sending). This function never
+                /// returns `error(would-block)`. If none of the datagrams were able to be sent, `ok(0)` is returned.
+                ///
+                /// This function semantically behaves the same as iterating the `datagrams` list and sequentially
+                /// sending each individual datagram until either the end of the list has been reached or the first error occurred.
+                /// If at least one datagram has been sent successfully, this function never returns an error.
+                ///
+                /// If the input list is empty, the function returns `ok(0)`.
+                ///
+                /// Each call to `send` must be permitted by a preceding `check-send`. Implementations must trap if
+                /// either `check-send` was not called or `datagrams` contains more items than `check-send` permitted.
+                ///
+                /// # Typical errors
+                /// - `invalid-argument`:        The `remote-address` has the wrong address family. (EAFNOSUPPORT)
+                /// - `invalid-argument`:        The IP address in `remote-address` is set to INADDR_ANY (`0.0.0.0` / `::`). (EDESTADDRREQ, EADDRNOTAVAIL)
+                /// - `invalid-argument`:        The port in `remote-address` is set to 0. (EDESTADDRREQ, EADDRNOTAVAIL)
+                /// - `invalid-argument`:        The socket is in "connected" mode and `remote-address` is `some` value that does not match the address passed to `stream`. (EISCONN)
+                /// - `invalid-argument`:        The socket is not "connected" and no value for `remote-address` was provided. (EDESTADDRREQ)
+                /// - `remote-unreachable`:      The remote address is not reachable. (ECONNRESET, ENETRESET on Windows, EHOSTUNREACH, EHOSTDOWN, ENETUNREACH, ENETDOWN, ENONET)
+                /// - `connection-refused`:      The connection was refused. (ECONNREFUSED)
+                /// - `datagram-too-large`:      The datagram is too large. (EMSGSIZE)
+                ///
+                /// # References
+                /// - <https://pubs.opengroup.org/onlinepubs/9699919799/functions/sendto.html>
+                /// - <https://pubs.opengroup.org/onlinepubs/9699919799/functions/sendmsg.html>
+                /// - <https://man7.org/linux/man-pages/man2/send.2.html>
+                /// - <https://man7.org/linux/man-pages/man2/sendmmsg.2.html>
+                /// - <https://learn.microsoft.com/en-us/windows/win32/api/winsock2/nf-winsock2-send>
+                /// - <https://learn.microsoft.com/en-us/windows/win32/api/winsock2/nf-winsock2-sendto>
+                /// - <https://learn.microsoft.com/en-us/windows/win32/api/winsock2/nf-winsock2-wsasendmsg>
+                /// - <https://man.freebsd.org/cgi/man.cgi?query=send&sektion=2>
+                pub fn send(&self, datagrams: &[OutgoingDatagram]) -> Result<u64, ErrorCode> {
+                    unsafe {
+                        #[repr(align(8))]
+                        struct RetArea([::core::mem::MaybeUninit<u8>; 16]);
+                        let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 16]);
+                        let vec7 = datagrams;
+                        let len7 = vec7.len();
+                        let layout7 =
+                            _rt::alloc::Layout::from_size_align_unchecked(vec7.len() * 44, 4);
+                        let result7 = if layout7.size() != 0 {
+                            let ptr = _rt::alloc::alloc(layout7).cast::<u8>();
+                            if ptr.is_null() {
+                                _rt::alloc::handle_alloc_error(layout7);
+                            }
+                            ptr
+                        } else {
+                            {
+                                ::core::ptr::null_mut()
+                            }
+                        };
+                        for (i, e) in vec7.into_iter().enumerate() {
+                            let base = result7.add(i * 44);
+                            {
+                                let OutgoingDatagram {
+                                    data: data0,
+                                    remote_address: remote_address0,
+                                } = e;
+                                let vec1 = data0;
+                                let ptr1 = vec1.as_ptr().cast::<u8>();
+                                let len1 = vec1.len();
+                                *base.add(4).cast::<usize>() = len1;
+                                *base.add(0).cast::<*mut u8>() = ptr1.cast_mut();
+                                match remote_address0 {
+                                    Some(e) => {
+                                        *base.add(8).cast::<u8>() = (1i32) as u8;
+                                        use super::super::super::wasi::sockets::network::IpSocketAddress as V6;
+                                        match e {
+                                            V6::Ipv4(e) => {
+                                                *base.add(12).cast::<u8>() = (0i32) as u8;
+                                                let super::super::super::wasi::sockets::network::Ipv4SocketAddress{ port:port2, address:address2, } = e;
+                                                *base.add(16).cast::<u16>() =
+                                                    (_rt::as_i32(port2)) as u16;
+                                                let (t3_0, t3_1, t3_2, t3_3) = address2;
+                                                *base.add(18).cast::<u8>() =
+                                                    (_rt::as_i32(t3_0)) as u8;
+                                                *base.add(19).cast::<u8>() =
+                                                    (_rt::as_i32(t3_1)) as u8;
+                                                *base.add(20).cast::<u8>() =
+                                                    (_rt::as_i32(t3_2)) as u8;
+                                                *base.add(21).cast::<u8>() =
+                                                    (_rt::as_i32(t3_3)) as u8;
+                                            }
+                                            V6::Ipv6(e) => {
+                                                *base.add(12).cast::<u8>() = (1i32) as u8;
+                                                let super::super::super::wasi::sockets::network::Ipv6SocketAddress{ port:port4, flow_info:flow_info4, address:address4, scope_id:scope_id4, } = e;
+                                                *base.add(16).cast::<u16>() =
+                                                    (_rt::as_i32(port4)) as u16;
+                                                *base.add(20).cast::<i32>() =
+                                                    _rt::as_i32(flow_info4);
+                                                let (
+                                                    t5_0,
+                                                    t5_1,
+                                                    t5_2,
+                                                    t5_3,
+                                                    t5_4,
+                                                    t5_5,
+                                                    t5_6,
+                                                    t5_7,
+                                                ) = address4;
+                                                *base.add(24).cast::<u16>() =
+                                                    (_rt::as_i32(t5_0)) as u16;
+                                                *base.add(26).cast::<u16>() =
+                                                    (_rt::as_i32(t5_1)) as u16;
+                                                *base.add(28).cast::<u16>() =
+                                                    (_rt::as_i32(t5_2)) as u16;
+                                                *base.add(30).cast::<u16>() =
+                                                    (_rt::as_i32(t5_3)) as u16;
+                                                *base.add(32).cast::<u16>() =
+                                                    (_rt::as_i32(t5_4)) as u16;
+                                                *base.add(34).cast::<u16>() =
+                                                    (_rt::as_i32(t5_5)) as u16;
+                                                *base.add(36).cast::<u16>() =
+                                                    (_rt::as_i32(t5_6)) as u16;
+                                                *base.add(38).cast::<u16>() =
+                                                    (_rt::as_i32(t5_7)) as u16;
+                                                *base.add(40).cast::<i32>() =
+                                                    _rt::as_i32(scope_id4);
+                                            }
+                                        }
+                                    }
+                                    None => {
+                                        *base.add(8).cast::<u8>() = (0i32) as u8;
+                                    }
+                                };
+                            }
+                        }
+                        let ptr8 = ret_area.0.as_mut_ptr().cast::<u8>();
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]outgoing-datagram-stream.send"]
+                            fn wit_import(_: i32, _: *mut u8, _: usize, _: *mut u8);
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(_: i32, _: *mut u8, _: usize, _: *mut u8) {
+                            unreachable!()
+                        }
+                        wit_import((self).handle() as i32, result7, len7, ptr8);
+                        let l9 = i32::from(*ptr8.add(0).cast::<u8>());
+                        if layout7.size() != 0 {
+                            _rt::alloc::dealloc(result7.cast(), layout7);
+                        }
+                        match l9 {
+                            0 => {
+                                let e = {
+                                    let l10 = *ptr8.add(8).cast::<i64>();
+
+                                    l10 as u64
+                                };
+                                Ok(e)
+                            }
+                            1 => {
+                                let e = {
+                                    let l11 = i32::from(*ptr8.add(8).cast::<u8>());
+
+                                    super::super::super::wasi::sockets::network::ErrorCode::_lift(
+                                        l11 as u8,
+                                    )
+                                };
+                                Err(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
+                        }
+                    }
+                }
+            }
+            impl OutgoingDatagramStream {
+                #[allow(unused_unsafe, clippy::all)]
+                /// Create a `pollable` which will resolve once the stream is ready to send again.
+                ///
+                /// Note: this function is here for WASI Preview2 only.
+                /// It's planned to be removed when `future` is natively supported in Preview3.
+                pub fn subscribe(&self) -> Pollable {
+                    unsafe {
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/udp@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]outgoing-datagram-stream.subscribe"]
+                            fn wit_import(_: i32) -> i32;
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(_: i32) -> i32 {
+                            unreachable!()
+                        }
+                        let ret = wit_import((self).handle() as i32);
+                        super::super::super::wasi::io::poll::Pollable::from_handle(ret as u32)
+                    }
+                }
+            }
+        }
+
+        #[allow(dead_code, clippy::all)]
+        pub mod udp_create_socket {
+            #[used]
+            #[doc(hidden)]
+            #[cfg(target_arch = "wasm32")]
+            static __FORCE_SECTION_REF: fn() =
+                super::super::super::__link_custom_section_describing_imports;
+            use super::super::super::_rt;
+            pub type ErrorCode = super::super::super::wasi::sockets::network::ErrorCode;
+            pub type IpAddressFamily = super::super::super::wasi::sockets::network::IpAddressFamily;
+            pub type UdpSocket = super::super::super::wasi::sockets::udp::UdpSocket;
+            #[allow(unused_unsafe, clippy::all)]
+            /// Create a new UDP socket.
+            ///
+            /// Similar to `socket(AF_INET or AF_INET6, SOCK_DGRAM, IPPROTO_UDP)` in POSIX.
+            /// On IPv6 sockets, IPV6_V6ONLY is enabled by default and can't be configured otherwise.
+            ///
+            /// This function does not require a network capability handle. This is considered to be safe because
+            /// at time of creation, the socket is not bound to any `network` yet. Up to the moment `bind` is called,
+            /// the socket is effectively an in-memory configuration object, unable to communicate with the outside world.
+            ///
+            /// All sockets are non-blocking. Use the wasi-poll interface to block on asynchronous operations.
+            ///
+            /// # Typical errors
+            /// - `not-supported`:     The specified `address-family` is not supported. (EAFNOSUPPORT)
+            /// - `new-socket-limit`:  The new socket resource could not be created because of a system limit. (EMFILE, ENFILE)
+            ///
+            /// # References:
+            /// - <https://pubs.opengroup.org/onlinepubs/9699919799/functions/socket.html>
+            /// - <https://man7.org/linux/man-pages/man2/socket.2.html>
+            /// - <https://learn.microsoft.com/en-us/windows/win32/api/winsock2/nf-winsock2-wsasocketw>
+            /// - <https://man.freebsd.org/cgi/man.cgi?query=socket&sektion=2>
+            pub fn create_udp_socket(
+                address_family: IpAddressFamily,
+            ) -> Result<UdpSocket, ErrorCode> {
+                unsafe {
+                    #[repr(align(4))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 8]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 8]);
+                    let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "wasi:sockets/udp-create-socket@0.2.0")]
+                    extern "C" {
+                        #[link_name = "create-udp-socket"]
+                        fn wit_import(_: i32, _: *mut u8);
+                    }
+
+                    #[cfg(not(target_arch = "wasm32"))]
+                    fn wit_import(_: i32, _: *mut u8) {
+                        unreachable!()
+                    }
+                    wit_import(address_family.clone() as i32, ptr0);
+                    let l1 = i32::from(*ptr0.add(0).cast::<u8>());
+                    match l1 {
+                        0 => {
+                            let e = {
+                                let l2 = *ptr0.add(4).cast::<i32>();
+
+                                super::super::super::wasi::sockets::udp::UdpSocket::from_handle(
+                                    l2 as u32,
+                                )
+                            };
+                            Ok(e)
+                        }
+                        1 => {
+                            let e = {
+                                let l3 = i32::from(*ptr0.add(4).cast::<u8>());
+
+                                super::super::super::wasi::sockets::network::ErrorCode::_lift(
+                                    l3 as u8,
+                                )
+                            };
+                            Err(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    }
+                }
+            }
+        }
+
+        #[allow(dead_code, clippy::all)]
+        pub mod ip_name_lookup {
+            #[used]
+            #[doc(hidden)]
+            #[cfg(target_arch = "wasm32")]
+            static __FORCE_SECTION_REF: fn() =
+                super::super::super::__link_custom_section_describing_imports;
+            use super::super::super::_rt;
+            pub type Pollable = super::super::super::wasi::io::poll::Pollable;
+            pub type Network = super::super::super::wasi::sockets::network::Network;
+            pub type ErrorCode = super::super::super::wasi::sockets::network::ErrorCode;
+            pub type IpAddress = super::super::super::wasi::sockets::network::IpAddress;
+
+            #[derive(Debug)]
+            #[repr(transparent)]
+            pub struct ResolveAddressStream {
+                handle: _rt::Resource<ResolveAddressStream>,
+            }
+
+            impl ResolveAddressStream {
+                #[doc(hidden)]
+                pub unsafe fn from_handle(handle: u32) -> Self {
+                    Self {
+                        handle: _rt::Resource::from_handle(handle),
+                    }
+                }
+
+                #[doc(hidden)]
+                pub fn take_handle(&self) -> u32 {
+                    _rt::Resource::take_handle(&self.handle)
+                }
+
+                #[doc(hidden)]
+                pub fn handle(&self) -> u32 {
+                    _rt::Resource::handle(&self.handle)
+                }
+            }
+
+            unsafe impl _rt::WasmResource for ResolveAddressStream {
+                #[inline]
+                unsafe fn drop(_handle: u32) {
+                    #[cfg(not(target_arch = "wasm32"))]
+                    unreachable!();
+
+                    #[cfg(target_arch = "wasm32")]
+                    {
+                        #[link(wasm_import_module = "wasi:sockets/ip-name-lookup@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[resource-drop]resolve-address-stream"]
+                            fn drop(_: u32);
+                        }
+
+                        drop(_handle);
+                    }
+                }
+            }
+
+            #[allow(unused_unsafe, clippy::all)]
+            /// Resolve an internet host name to a list of IP addresses.
+            ///
+            /// Unicode domain names are automatically converted to ASCII using IDNA encoding.
+            /// If the input is an IP address string, the address is parsed and returned
+            /// as-is without making any external requests.
+            ///
+            /// See the wasi-socket proposal README.md for a comparison with getaddrinfo.
+            ///
+            /// This function never blocks. It either immediately fails or immediately
+            /// returns successfully with a `resolve-address-stream` that can be used
+            /// to (asynchronously) fetch the results.
+            ///
+            /// # Typical errors
+            /// - `invalid-argument`: `name` is a syntactically invalid domain name or IP address.
+            ///
+            /// # References:
+            /// - <https://pubs.opengroup.org/onlinepubs/9699919799/functions/getaddrinfo.html>
+            /// - <https://man7.org/linux/man-pages/man3/getaddrinfo.3.html>
+            /// - <https://learn.microsoft.com/en-us/windows/win32/api/ws2tcpip/nf-ws2tcpip-getaddrinfo>
+            /// - <https://man.freebsd.org/cgi/man.cgi?query=getaddrinfo&sektion=3>
+            pub fn resolve_addresses(
+                network: &Network,
+                name: &str,
+            ) -> Result<ResolveAddressStream, ErrorCode> {
+                unsafe {
+                    #[repr(align(4))]
+                    struct RetArea([::core::mem::MaybeUninit<u8>; 8]);
+                    let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 8]);
+                    let vec0 = name;
+                    let ptr0 = vec0.as_ptr().cast::<u8>();
+                    let len0 = vec0.len();
+                    let ptr1 = ret_area.0.as_mut_ptr().cast::<u8>();
+                    #[cfg(target_arch = "wasm32")]
+                    #[link(wasm_import_module = "wasi:sockets/ip-name-lookup@0.2.0")]
+                    extern "C" {
+                        #[link_name = "resolve-addresses"]
+                        fn wit_import(_: i32, _: *mut u8, _: usize, _: *mut u8);
+                    }
+
+                    #[cfg(not(target_arch = "wasm32"))]
+                    fn wit_import(_: i32, _: *mut u8, _: usize, _: *mut u8) {
+                        unreachable!()
+                    }
+                    wit_import((network).handle() as i32, ptr0.cast_mut(), len0, ptr1);
+                    let l2 = i32::from(*ptr1.add(0).cast::<u8>());
+                    match l2 {
+                        0 => {
+                            let e = {
+                                let l3 = *ptr1.add(4).cast::<i32>();
+
+                                ResolveAddressStream::from_handle(l3 as u32)
+                            };
+                            Ok(e)
+                        }
+                        1 => {
+                            let e = {
+                                let l4 = i32::from(*ptr1.add(4).cast::<u8>());
+
+                                super::super::super::wasi::sockets::network::ErrorCode::_lift(
+                                    l4 as u8,
+                                )
+                            };
+                            Err(e)
+                        }
+                        _ => _rt::invalid_enum_discriminant(),
+                    }
+                }
+            }
+            impl ResolveAddressStream {
+                #[allow(unused_unsafe, clippy::all)]
+                /// Returns the next address from the resolver.
+                ///
+                /// This function should be called multiple times. On each call, it will
+                /// return the next address in connection order preference. If all
+                /// addresses have been exhausted, this function returns `none`.
+                ///
+                /// This function never returns IPv4-mapped IPv6 addresses.
+                ///
+                /// # Typical errors
+                /// - `name-unresolvable`:          Name does not exist or has no suitable associated IP addresses. (EAI_NONAME, EAI_NODATA, EAI_ADDRFAMILY)
+                /// - `temporary-resolver-failure`: A temporary failure in name resolution occurred. (EAI_AGAIN)
+                /// - `permanent-resolver-failure`: A permanent failure in name resolution occurred. (EAI_FAIL)
+                /// - `would-block`:                A result is not available yet. (EWOULDBLOCK, EAGAIN)
+                pub fn resolve_next_address(&self) -> Result<Option<IpAddress>, ErrorCode> {
+                    unsafe {
+                        #[repr(align(2))]
+                        struct RetArea([::core::mem::MaybeUninit<u8>; 22]);
+                        let mut ret_area = RetArea([::core::mem::MaybeUninit::uninit(); 22]);
+                        let ptr0 = ret_area.0.as_mut_ptr().cast::<u8>();
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/ip-name-lookup@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]resolve-address-stream.resolve-next-address"]
+                            fn wit_import(_: i32, _: *mut u8);
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(_: i32, _: *mut u8) {
+                            unreachable!()
+                        }
+                        wit_import((self).handle() as i32, ptr0);
+                        let l1 = i32::from(*ptr0.add(0).cast::<u8>());
+                        match l1 {
+                            0 => {
+                                let e = {
+                                    let l2 = i32::from(*ptr0.add(2).cast::<u8>());
+
+                                    match l2 {
+                                        0 => None,
+                                        1 => {
+                                            let e = {
+                                                let l3 = i32::from(*ptr0.add(4).cast::<u8>());
+                                                use super::super::super::wasi::sockets::network::IpAddress as V16;
+                                                let v16 = match l3 {
+                                                    0 => {
+                                                        let e16 = {
+                                                            let l4 = i32::from(
+                                                                *ptr0.add(6).cast::<u8>(),
+                                                            );
+                                                            let l5 = i32::from(
+                                                                *ptr0.add(7).cast::<u8>(),
+                                                            );
+                                                            let l6 = i32::from(
+                                                                *ptr0.add(8).cast::<u8>(),
+                                                            );
+                                                            let l7 = i32::from(
+                                                                *ptr0.add(9).cast::<u8>(),
+                                                            );
+
+                                                            (l4 as u8, l5 as u8, l6 as u8, l7 as u8)
+                                                        };
+                                                        V16::Ipv4(e16)
+                                                    }
+                                                    n => {
+                                                        debug_assert_eq!(
+                                                            n, 1,
+                                                            "invalid enum discriminant"
+                                                        );
+                                                        let e16 = {
+                                                            let l8 = i32::from(
+                                                                *ptr0.add(6).cast::<u16>(),
+                                                            );
+                                                            let l9 = i32::from(
+                                                                *ptr0.add(8).cast::<u16>(),
+                                                            );
+                                                            let l10 = i32::from(
+                                                                *ptr0.add(10).cast::<u16>(),
+                                                            );
+                                                            let l11 = i32::from(
+                                                                *ptr0.add(12).cast::<u16>(),
+                                                            );
+                                                            let l12 = i32::from(
+                                                                *ptr0.add(14).cast::<u16>(),
+                                                            );
+                                                            let l13 = i32::from(
+                                                                *ptr0.add(16).cast::<u16>(),
+                                                            );
+                                                            let l14 = i32::from(
+                                                                *ptr0.add(18).cast::<u16>(),
+                                                            );
+                                                            let l15 = i32::from(
+                                                                *ptr0.add(20).cast::<u16>(),
+                                                            );
+
+                                                            (
+                                                                l8 as u16, l9 as u16, l10 as u16,
+                                                                l11 as u16, l12 as u16, l13 as u16,
+                                                                l14 as u16, l15 as u16,
+                                                            )
+                                                        };
+                                                        V16::Ipv6(e16)
+                                                    }
+                                                };
+
+                                                v16
+                                            };
+                                            Some(e)
+                                        }
+                                        _ => _rt::invalid_enum_discriminant(),
+                                    }
+                                };
+                                Ok(e)
+                            }
+                            1 => {
+                                let e = {
+                                    let l17 = i32::from(*ptr0.add(2).cast::<u8>());
+
+                                    super::super::super::wasi::sockets::network::ErrorCode::_lift(
+                                        l17 as u8,
+                                    )
+                                };
+                                Err(e)
+                            }
+                            _ => _rt::invalid_enum_discriminant(),
+                        }
+                    }
+                }
+            }
+            impl ResolveAddressStream {
+                #[allow(unused_unsafe, clippy::all)]
+                /// Create a `pollable` which will resolve once the stream is ready for I/O.
+                ///
+                /// Note: this function is here for WASI Preview2 only.
+                /// It's planned to be removed when `future` is natively supported in Preview3.
+                pub fn subscribe(&self) -> Pollable {
+                    unsafe {
+                        #[cfg(target_arch = "wasm32")]
+                        #[link(wasm_import_module = "wasi:sockets/ip-name-lookup@0.2.0")]
+                        extern "C" {
+                            #[link_name = "[method]resolve-address-stream.subscribe"]
+                            fn wit_import(_: i32) -> i32;
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        fn wit_import(_: i32) -> i32 {
+                            unreachable!()
+                        }
+                        let ret = wit_import((self).handle() as i32);
+                        super::super::super::wasi::io::poll::Pollable::from_handle(ret as u32)
+                    }
+                }
+            }
+        }
     }
 }
 mod _rt {
@@ -3984,14 +5931,21 @@ mod _rt {
             self as i32
         }
     }
+    pub unsafe fn cabi_dealloc(ptr: *mut u8, size: usize, align: usize) {
+        if size == 0 {
+            return;
+        }
+        let layout = alloc::Layout::from_size_align_unchecked(size, align);
+        alloc::dealloc(ptr as *mut u8, layout);
+    }
     extern crate alloc as alloc_crate;
 }
 
 #[cfg(target_arch = "wasm32")]
 #[link_section = "component-type:wit-bindgen:0.25.0:wasm-runtime:encoded world"]
 #[doc(hidden)]
-pub static __WIT_BINDGEN_COMPONENT_TYPE: [u8; 4391] = *b"\
-\0asm\x0d\0\x01\0\0\x19\x16wit-component-encoding\x04\0\x07\xa4!\x01A\x02\x01A\x1a\
+pub static __WIT_BINDGEN_COMPONENT_TYPE: [u8; 6307] = *b"\
+\0asm\x0d\0\x01\0\0\x19\x16wit-component-encoding\x04\0\x07\xa00\x01A\x02\x01A\"\
 \x01B\x0a\x04\0\x08pollable\x03\x01\x01h\0\x01@\x01\x04self\x01\0\x7f\x04\0\x16[\
 method]pollable.ready\x01\x02\x01@\x01\x04self\x01\x01\0\x04\0\x16[method]pollab\
 le.block\x01\x03\x01p\x01\x01py\x01@\x01\x02in\x04\0\x05\x04\0\x04poll\x01\x06\x03\
@@ -4077,10 +6031,50 @@ ip-address-family\x03\0\x04\x02\x03\x02\x01\x0f\x04\0\x0atcp-socket\x03\0\x06\x0
 i\x07\x01j\x01\x08\x01\x03\x01@\x01\x0eaddress-family\x05\0\x09\x04\0\x11create-\
 tcp-socket\x01\x0a\x03\x01$wasi:sockets/tcp-create-socket@0.2.0\x05\x10\x01B\x05\
 \x02\x03\x02\x01\x0a\x04\0\x07network\x03\0\0\x01i\x01\x01@\0\0\x02\x04\0\x10ins\
-tance-network\x01\x03\x03\x01#wasi:sockets/instance-network@0.2.0\x05\x11\x04\x01\
-#component:wasm-runtime/wasm-runtime\x04\0\x0b\x12\x01\0\x0cwasm-runtime\x03\0\0\
-\0G\x09producers\x01\x0cprocessed-by\x02\x0dwit-component\x070.208.1\x10wit-bind\
-gen-rust\x060.25.0";
+tance-network\x01\x03\x03\x01#wasi:sockets/instance-network@0.2.0\x05\x11\x01BD\x02\
+\x03\x02\x01\x01\x04\0\x08pollable\x03\0\0\x02\x03\x02\x01\x0a\x04\0\x07network\x03\
+\0\x02\x02\x03\x02\x01\x0b\x04\0\x0aerror-code\x03\0\x04\x02\x03\x02\x01\x0c\x04\
+\0\x11ip-socket-address\x03\0\x06\x02\x03\x02\x01\x0d\x04\0\x11ip-address-family\
+\x03\0\x08\x01p}\x01r\x02\x04data\x0a\x0eremote-address\x07\x04\0\x11incoming-da\
+tagram\x03\0\x0b\x01k\x07\x01r\x02\x04data\x0a\x0eremote-address\x0d\x04\0\x11ou\
+tgoing-datagram\x03\0\x0e\x04\0\x0audp-socket\x03\x01\x04\0\x18incoming-datagram\
+-stream\x03\x01\x04\0\x18outgoing-datagram-stream\x03\x01\x01h\x10\x01h\x03\x01j\
+\0\x01\x05\x01@\x03\x04self\x13\x07network\x14\x0dlocal-address\x07\0\x15\x04\0\x1d\
+[method]udp-socket.start-bind\x01\x16\x01@\x01\x04self\x13\0\x15\x04\0\x1e[metho\
+d]udp-socket.finish-bind\x01\x17\x01i\x11\x01i\x12\x01o\x02\x18\x19\x01j\x01\x1a\
+\x01\x05\x01@\x02\x04self\x13\x0eremote-address\x0d\0\x1b\x04\0\x19[method]udp-s\
+ocket.stream\x01\x1c\x01j\x01\x07\x01\x05\x01@\x01\x04self\x13\0\x1d\x04\0\x20[m\
+ethod]udp-socket.local-address\x01\x1e\x04\0![method]udp-socket.remote-address\x01\
+\x1e\x01@\x01\x04self\x13\0\x09\x04\0![method]udp-socket.address-family\x01\x1f\x01\
+j\x01}\x01\x05\x01@\x01\x04self\x13\0\x20\x04\0$[method]udp-socket.unicast-hop-l\
+imit\x01!\x01@\x02\x04self\x13\x05value}\0\x15\x04\0([method]udp-socket.set-unic\
+ast-hop-limit\x01\"\x01j\x01w\x01\x05\x01@\x01\x04self\x13\0#\x04\0&[method]udp-\
+socket.receive-buffer-size\x01$\x01@\x02\x04self\x13\x05valuew\0\x15\x04\0*[meth\
+od]udp-socket.set-receive-buffer-size\x01%\x04\0#[method]udp-socket.send-buffer-\
+size\x01$\x04\0'[method]udp-socket.set-send-buffer-size\x01%\x01i\x01\x01@\x01\x04\
+self\x13\0&\x04\0\x1c[method]udp-socket.subscribe\x01'\x01h\x11\x01p\x0c\x01j\x01\
+)\x01\x05\x01@\x02\x04self(\x0bmax-resultsw\0*\x04\0([method]incoming-datagram-s\
+tream.receive\x01+\x01@\x01\x04self(\0&\x04\0*[method]incoming-datagram-stream.s\
+ubscribe\x01,\x01h\x12\x01@\x01\x04self-\0#\x04\0+[method]outgoing-datagram-stre\
+am.check-send\x01.\x01p\x0f\x01@\x02\x04self-\x09datagrams/\0#\x04\0%[method]out\
+going-datagram-stream.send\x010\x01@\x01\x04self-\0&\x04\0*[method]outgoing-data\
+gram-stream.subscribe\x011\x03\x01\x16wasi:sockets/udp@0.2.0\x05\x12\x02\x03\0\x08\
+\x0audp-socket\x01B\x0c\x02\x03\x02\x01\x0a\x04\0\x07network\x03\0\0\x02\x03\x02\
+\x01\x0b\x04\0\x0aerror-code\x03\0\x02\x02\x03\x02\x01\x0d\x04\0\x11ip-address-f\
+amily\x03\0\x04\x02\x03\x02\x01\x13\x04\0\x0audp-socket\x03\0\x06\x01i\x07\x01j\x01\
+\x08\x01\x03\x01@\x01\x0eaddress-family\x05\0\x09\x04\0\x11create-udp-socket\x01\
+\x0a\x03\x01$wasi:sockets/udp-create-socket@0.2.0\x05\x14\x02\x03\0\x02\x0aip-ad\
+dress\x01B\x16\x02\x03\x02\x01\x01\x04\0\x08pollable\x03\0\0\x02\x03\x02\x01\x0a\
+\x04\0\x07network\x03\0\x02\x02\x03\x02\x01\x0b\x04\0\x0aerror-code\x03\0\x04\x02\
+\x03\x02\x01\x15\x04\0\x0aip-address\x03\0\x06\x04\0\x16resolve-address-stream\x03\
+\x01\x01h\x08\x01k\x07\x01j\x01\x0a\x01\x05\x01@\x01\x04self\x09\0\x0b\x04\03[me\
+thod]resolve-address-stream.resolve-next-address\x01\x0c\x01i\x01\x01@\x01\x04se\
+lf\x09\0\x0d\x04\0([method]resolve-address-stream.subscribe\x01\x0e\x01h\x03\x01\
+i\x08\x01j\x01\x10\x01\x05\x01@\x02\x07network\x0f\x04names\0\x11\x04\0\x11resol\
+ve-addresses\x01\x12\x03\x01!wasi:sockets/ip-name-lookup@0.2.0\x05\x16\x04\x01#c\
+omponent:wasm-runtime/wasm-runtime\x04\0\x0b\x12\x01\0\x0cwasm-runtime\x03\0\0\0\
+G\x09producers\x01\x0cprocessed-by\x02\x0dwit-component\x070.208.1\x10wit-bindge\
+n-rust\x060.25.0";
 
 #[inline(never)]
 #[doc(hidden)]
@@ -1,225 +1,581 @@
-use crate::Timer;
-use crate::{bindings::wasi::io::poll::Pollable, poll_tasks::PollTasks};
-use crate::{io::timer::TIMERS, poll_tasks::EventWithWaker};
+use crate::local::{LocalSet, LocalTasks};
+use crate::task_store::{TaskId, TaskStore};
+use crate::timer_queue::{TimerId, TimerQueue};
+use crate::{
+    bindings::wasi::io::poll::Pollable,
+    poll_tasks::{EventWithWaker, PollTasks, PollableKey},
+};
 use crossbeam::queue::SegQueue;
 use futures::channel::oneshot;
 use futures::FutureExt;
-use lazy_static::lazy_static;
-use std::collections::hash_map::Entry;
-use std::collections::HashMap;
-use std::sync::RwLock;
+use std::any::Any;
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Weak;
+use std::time::{Duration, Instant};
 use std::{
     future::Future,
     pin::Pin,
     sync::{Arc, Mutex},
-    task::{Context, Wake, Waker},
+    task::{Context, Waker},
 };
-use uuid::Uuid;
-lazy_static! {
-    /// The global reactor for this runtime
-    pub static ref REACTOR: Reactor<'static> = Reactor::default();
-    //queue for ready tasks
-    #[derive(Debug)]
-    pub static ref READY_QUEUE: SegQueue<Uuid> = SegQueue::new();
 
+//boxed body of a spawned task
+type SendFuture<'a> = dyn Future<Output = ()> + Send + 'a;
+
+//task polls between two checks of timers and I/O while tasks keep being ready
+const EVENT_INTERVAL: usize = 61;
+
+thread_local! {
+    //handle of the runtime currently driving block_on on this thread
+    static CURRENT: RefCell<Option<Handle>> = const { RefCell::new(None) };
 }
+
 /// The async engine instance
 pub struct WasmRuntimeAsyncEngine;
 
-/// the reactor that processes poll submissions. Still Experimental
-struct Task<'a> {
-    id: Uuid,
-    task: Pin<Box<dyn Future<Output = ()> + Send + 'a>>,
-    waker: Arc<FutureWaker>,
+/// A runtime instance that owns its reactor, ready queue and timers.
+/// Nothing is shared between two runtimes, so each one starts from a clean slate
+#[derive(Default)]
+pub struct Runtime {
+    handle: Handle,
 }
 
-impl<'a> Task<'a> {
-    fn new(task: Pin<Box<dyn Future<Output = ()> + Send + 'a>>) -> Self {
-        let id = Uuid::new_v4();
-        let waker = Arc::new(FutureWaker::new(id));
-        Self { id, task, waker }
-    }
+/// What the runtime does when a spawned task panics.
+/// The panic payload is always delivered through the task's [`JoinHandle`]
+#[derive(Clone, Default)]
+pub enum PanicPolicy {
+    /// drop the task and keep running the others
+    #[default]
+    Ignore,
+    /// drop the task and panic out of `block_on`
+    Abort,
+    /// drop the task and pass the panic payload to a hook. The hook must not panic itself
+    Hook(PanicHook),
+}
+
+/// callback receiving the payload of a panicked task
+pub type PanicHook = Arc<dyn Fn(&(dyn Any + Send)) + Send + Sync>;
+
+/// A reference to a [`Runtime`]. Leaf futures use [`Handle::current`] to find the reactor they run on
+#[derive(Clone, Default)]
+pub struct Handle {
+    reactor: Arc<Reactor<'static>>,
 }
+
+//how long block_on keeps driving spawned tasks after the root future resolved
+#[derive(Clone, Copy)]
+enum Shutdown {
+    Immediate,
+    WaitAll,
+    Timeout(Duration),
+}
+
+//restores the previously entered runtime once block_on returns
+struct EnterGuard {
+    previous: Option<Handle>,
+}
+
+/// the reactor that processes poll submissions. Still Experimental
 #[derive(Default)]
 pub struct Reactor<'a> {
     events: Mutex<PollTasks>,
-    spawn_queue: SegQueue<Task<'a>>, //right now the engine holds the tasks but depending
-    future_tasks: RwLock<HashMap<Uuid, Mutex<Task<'a>>>>,
-    timers: RwLock<HashMap<String, EventWithWaker<Timer>>>,
+    future_tasks: TaskStore<SendFuture<'a>>,
+    timers: Mutex<TimerQueue>,
+    panic_policy: PanicPolicy,
+    //set by a panicking task under PanicPolicy::Abort, checked once the task lock is released
+    abort_requested: AtomicBool,
 }
 
 pub struct JoinHandle<T> {
-    id: Uuid,
-    receiver: oneshot::Receiver<T>,
+    id: TaskId,
+    receiver: oneshot::Receiver<Result<T, JoinError>>,
+    finished: Arc<AtomicBool>,
+    //queue of the task store owning the task, weak so a handle stored inside a task does not keep it alive
+    cancel_queue: Weak<SegQueue<TaskId>>,
+}
+
+/// Error returned by a [`JoinHandle`] whose task did not run to completion
+#[derive(Debug)]
+pub struct JoinError {
+    repr: JoinErrorRepr,
+}
+
+#[derive(Debug)]
+enum JoinErrorRepr {
+    Cancelled,
+    Panicked(Box<dyn Any + Send + 'static>),
 }
 
+//marks the task as finished once its future completes or is dropped
+struct FinishGuard(Arc<AtomicBool>);
+
 impl<T> JoinHandle<T> {
+    //dropping the task drops its leaf futures, which release their timers and pollables
     pub fn cancel(&self) {
-        REACTOR.remove_task(self.id);
+        if let Some(cancel_queue) = self.cancel_queue.upgrade() {
+            cancel_queue.push(self.id);
+        }
+    }
+
+    /// whether the task has completed, panicked or been dropped after a cancel
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Acquire)
     }
 }
 
 impl<T> Future for JoinHandle<T> {
-    type Output = T;
+    type Output = Result<T, JoinError>;
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> std::task::Poll<Self::Output> {
         let this = self.get_mut();
         match this.receiver.poll_unpin(cx) {
             std::task::Poll::Ready(Ok(result)) => std::task::Poll::Ready(result),
-            std::task::Poll::Ready(_) => panic!("Could not join result"),
+            //the task was dropped before it could send its result
+            std::task::Poll::Ready(Err(_)) => std::task::Poll::Ready(Err(JoinError::cancelled())),
             std::task::Poll::Pending => std::task::Poll::Pending,
         }
     }
 }
+
+impl JoinError {
+    fn cancelled() -> Self {
+        Self {
+            repr: JoinErrorRepr::Cancelled,
+        }
+    }
+
+    fn panicked(payload: Box<dyn Any + Send + 'static>) -> Self {
+        Self {
+            repr: JoinErrorRepr::Panicked(payload),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self.repr, JoinErrorRepr::Cancelled)
+    }
+
+    pub fn is_panic(&self) -> bool {
+        matches!(self.repr, JoinErrorRepr::Panicked(_))
+    }
+
+    /// the payload the task panicked with.
+    ///
+    /// # Panics
+    /// Panics if the task was cancelled instead
+    pub fn into_panic(self) -> Box<dyn Any + Send + 'static> {
+        self.try_into_panic()
+            .expect("JoinError::into_panic called on a cancelled task")
+    }
+
+    pub fn try_into_panic(self) -> Result<Box<dyn Any + Send + 'static>, JoinError> {
+        match self.repr {
+            JoinErrorRepr::Panicked(payload) => Ok(payload),
+            repr => Err(Self { repr }),
+        }
+    }
+}
+
+impl std::fmt::Display for JoinError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.repr {
+            JoinErrorRepr::Cancelled => write!(f, "task was cancelled"),
+            JoinErrorRepr::Panicked(_) => write!(f, "task panicked"),
+        }
+    }
+}
+
+impl std::error::Error for JoinError {}
+
+impl std::fmt::Debug for PanicPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PanicPolicy::Ignore => write!(f, "Ignore"),
+            PanicPolicy::Abort => write!(f, "Abort"),
+            PanicPolicy::Hook(_) => write!(f, "Hook(..)"),
+        }
+    }
+}
+
+impl Drop for FinishGuard {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Release);
+    }
+}
 impl<'a> Reactor<'a> {
-    //adds event to the queue
-    pub fn register(&self, event_name: String, pollable: EventWithWaker<Arc<Pollable>>) {
-        self.events.lock().unwrap().push(event_name, pollable);
+    //adds event to the queue, the key identifies it from then on
+    pub fn register(&self, pollable: EventWithWaker<Arc<Pollable>>) -> PollableKey {
+        self.events.lock().unwrap().push(pollable)
+    }
+    //drops the registration whether or not the pollable became ready
+    pub fn deregister(&self, key: PollableKey) {
+        self.events.lock().unwrap().remove(key);
+    }
+    //swaps the waker of a registered pollable unless it would wake the same task
+    pub fn update_waker(&self, key: PollableKey, waker: &Waker) {
+        self.events.lock().unwrap().update_waker(key, waker);
     }
     //checks if descriptor has been added to the polling queue
-    pub fn is_pollable(&self, key: &str) -> bool {
+    pub fn is_pollable(&self, key: PollableKey) -> bool {
         self.events.lock().unwrap().contains(key)
     }
 
     //checks if timer is pollable
-    pub fn is_timer_pollable(&self, key: &str) -> bool {
-        self.timers.read().unwrap().contains_key(key)
+    pub fn is_timer_pollable(&self, id: TimerId) -> bool {
+        self.timers.lock().unwrap().contains(id)
     }
 
-    //polls event queue to see if any of the events are readycar
-    pub fn wait_for_io(&self) {
-        self.events.lock().unwrap().wait_for_pollables();
+    //polls event queue to see if any of the events are ready.
+    //Parks until the earliest timer or `deadline` is due when there is nothing left to run
+    pub fn wait_for_io(&self, deadline: Option<Instant>) {
+        let timeout = if !self.future_tasks.has_ready() {
+            let until_deadline =
+                deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            match (self.next_timer_timeout(), until_deadline) {
+                (Some(timer), Some(deadline)) => Some(timer.min(deadline)),
+                (timer, deadline) => timer.or(deadline),
+            }
+        } else {
+            Some(Duration::ZERO)
+        };
+        self.events.lock().unwrap().wait_for_pollables(timeout);
     }
 
     //checks if event is ready
-    pub fn check_ready(&self, event_name: &str) -> bool {
-        self.events.lock().unwrap().check_if_ready(event_name)
+    pub fn check_ready(&self, key: PollableKey) -> bool {
+        self.events.lock().unwrap().check_if_ready(key)
     }
 
     pub fn is_empty(&self) -> bool {
-        self.events.lock().unwrap().is_empty() && self.future_tasks.read().unwrap().is_empty()
+        self.events.lock().unwrap().is_empty() && self.future_tasks.is_empty()
     }
 
+    //wakes and drops every timer whose deadline has passed
     pub(crate) fn update_timers(&self) {
-        self.timers
-            .write()
-            .unwrap()
-            .iter_mut()
-            .for_each(|(_, cell)| {
-                cell.0.update_elapsed();
-                if cell.0.elapsed() {
-                    cell.1.wake_by_ref();
-                }
-            });
+        let expired = self.timers.lock().unwrap().expire(Instant::now());
+        //woken outside of the lock, waking only touches the ready queue
+        expired.into_iter().for_each(Waker::wake);
+    }
+
+    //time left until the earliest registered timer elapses
+    pub(crate) fn next_timer_timeout(&self) -> Option<Duration> {
+        let deadline = self.timers.lock().unwrap().next_deadline()?;
+        Some(deadline.saturating_duration_since(Instant::now()))
+    }
+
+    pub(crate) fn remove_timer(&self, id: TimerId) {
+        self.timers.lock().unwrap().remove(id);
     }
-    pub(crate) fn remove_task(&self, id: Uuid) {
-        self.future_tasks.write().unwrap().remove(&id);
-        //Todo: See if you can remove timers and sockets as well
+
+    //registers a timer under `id`, or a new id when there is none yet
+    pub(crate) fn register_timer(
+        &self,
+        id: Option<TimerId>,
+        deadline: Instant,
+        waker: Waker,
+    ) -> TimerId {
+        self.timers.lock().unwrap().insert(id, deadline, waker)
     }
 
-    pub(crate) fn timer_has_elapsed(&self, timer_key: &str) -> bool {
-        self.timers
-            .read()
-            .unwrap()
-            .get(timer_key)
-            .map(|s| s.0.elapsed())
-            .unwrap_or_default()
+    //moves the deadline of a registered timer, keeping the waker it was registered with
+    pub(crate) fn reset_timer(&self, id: TimerId, deadline: Instant) {
+        self.timers.lock().unwrap().reset(id, deadline);
     }
 
-    pub(crate) fn remove_timer(&self, timer_key: &str) {
-        self.timers.write().unwrap().remove(timer_key);
+    fn report_panic(&self, payload: &(dyn Any + Send)) {
+        match &self.panic_policy {
+            PanicPolicy::Ignore => {}
+            PanicPolicy::Abort => self.abort_requested.store(true, Ordering::Release),
+            PanicPolicy::Hook(hook) => hook(payload),
+        }
     }
+}
 
-    pub(crate) fn register_timer(&self, timer_name: String, event: EventWithWaker<Timer>) {
-        self.timers.write().unwrap().insert(timer_name, event);
+impl Reactor<'static> {
+    fn with_panic_policy(panic_policy: PanicPolicy) -> Self {
+        Self {
+            panic_policy,
+            ..Self::default()
+        }
     }
 
-    pub(crate) fn push_task<K: Send + 'a, F: Future<Output = K> + Send + 'static>(
-        &self,
+    pub(crate) fn push_task<K: Send + 'static, F: Future<Output = K> + Send + 'static>(
+        self: &Arc<Self>,
         future: F,
     ) -> JoinHandle<K> {
-        let (sender, receiver) = oneshot::channel();
-        let task = Task::new(Box::pin(async move {
-            let result = future.await;
-            let _ = sender.send(result);
-        }));
-        let id = task.id;
-        self.spawn_queue.push(task);
-        READY_QUEUE.push(id);
-        JoinHandle { id, receiver }
-    }
-
-    pub(crate) fn drain_queue(&self) {
-        while let Some(task) = self.spawn_queue.pop() {
-            self.future_tasks
-                .write()
-                .unwrap()
-                .insert(task.id, Mutex::new(task));
-        }
+        self.push_future(future, true)
+    }
+
+    fn push_future<K: Send + 'static, F: Future<Output = K> + Send + 'static>(
+        self: &Arc<Self>,
+        future: F,
+        apply_policy: bool,
+    ) -> JoinHandle<K> {
+        self.future_tasks.push(|id, cancel_queue| {
+            let (task, join_handle) = join_pair(id, future, cancel_queue, apply_policy);
+            (Box::pin(task), join_handle)
+        })
     }
 }
 
-impl WasmRuntimeAsyncEngine {
-    /// function to execute futures
-    pub fn block_on<K: Send + 'static, F: Future<Output = K> + Send + 'static>(future: F) -> K {
-        let reactor = &REACTOR;
-        let mut join_handle = reactor.push_task(future);
+//splits a future into the task body run by the executor and the JoinHandle observing it.
+//A panic is always caught and sent to the JoinHandle so it never unwinds through the event loop.
+//Only spawned tasks are subject to the panic policy, block_on rethrows a root panic itself
+pub(crate) fn join_pair<K: 'static, F: Future<Output = K> + 'static>(
+    id: TaskId,
+    future: F,
+    cancel_queue: &Arc<SegQueue<TaskId>>,
+    apply_policy: bool,
+) -> (impl Future<Output = ()>, JoinHandle<K>) {
+    let (sender, receiver) = oneshot::channel();
+    let finished = Arc::new(AtomicBool::new(false));
+    let guard = FinishGuard(finished.clone());
+    let task = async move {
+        let _guard = guard;
+        match AssertUnwindSafe(future).catch_unwind().await {
+            Ok(result) => {
+                let _ = sender.send(Ok(result));
+            }
+            Err(payload) => {
+                if let Some(handle) = Handle::try_current().filter(|_| apply_policy) {
+                    handle.reactor.report_panic(payload.as_ref());
+                }
+                let _ = sender.send(Err(JoinError::panicked(payload)));
+            }
+        }
+    };
+    let join_handle = JoinHandle {
+        id,
+        receiver,
+        finished,
+        cancel_queue: Arc::downgrade(cancel_queue),
+    };
+    (task, join_handle)
+}
+
+impl Runtime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// creates a runtime that handles panicking tasks according to `panic_policy`
+    pub fn with_panic_policy(panic_policy: PanicPolicy) -> Self {
+        Self {
+            handle: Handle {
+                reactor: Arc::new(Reactor::with_panic_policy(panic_policy)),
+            },
+        }
+    }
+
+    /// handle to this runtime, which can spawn tasks from outside of it
+    pub fn handle(&self) -> Handle {
+        self.handle.clone()
+    }
+
+    /// function to execute futures. Returns as soon as `future` resolves,
+    /// any task still running at that point is dropped
+    pub fn block_on<K: Send + 'static, F: Future<Output = K> + Send + 'static>(
+        &self,
+        future: F,
+    ) -> K {
+        let join_handle = self.handle.reactor.push_future(future, false);
+        self.run(join_handle, None, Shutdown::Immediate)
+    }
+
+    /// like [`Runtime::block_on`] for a root future that is not `Send`.
+    /// The future runs inside a fresh [`LocalSet`], so it can use [`spawn_local`](crate::local::spawn_local)
+    pub fn block_on_local<K: 'static, F: Future<Output = K> + 'static>(&self, future: F) -> K {
+        LocalSet::new().block_on(self, future)
+    }
+
+    /// like [`Runtime::block_on`] but keeps running until every spawned task has finished
+    pub fn block_on_all<K: Send + 'static, F: Future<Output = K> + Send + 'static>(
+        &self,
+        future: F,
+    ) -> K {
+        let join_handle = self.handle.reactor.push_future(future, false);
+        self.run(join_handle, None, Shutdown::WaitAll)
+    }
+
+    /// like [`Runtime::block_on`] but gives the remaining tasks up to `timeout`
+    /// to finish once `future` has resolved, dropping whatever is left after that
+    pub fn block_on_with_shutdown_timeout<
+        K: Send + 'static,
+        F: Future<Output = K> + Send + 'static,
+    >(
+        &self,
+        future: F,
+        timeout: Duration,
+    ) -> K {
+        let join_handle = self.handle.reactor.push_future(future, false);
+        self.run(join_handle, None, Shutdown::Timeout(timeout))
+    }
+
+    //drives the root task alongside the local set it was spawned on
+    pub(crate) fn run_local<K>(&self, join_handle: JoinHandle<K>, local: &LocalTasks) -> K {
+        self.run(join_handle, Some(local), Shutdown::Immediate)
+    }
+
+    fn run<K>(
+        &self,
+        mut join_handle: JoinHandle<K>,
+        local: Option<&LocalTasks>,
+        shutdown: Shutdown,
+    ) -> K {
+        let _guard = self.handle.enter();
+        let reactor = &self.handle.reactor;
+        let shutdown_guard = ShutdownGuard { reactor, local };
+        let drain = || {
+            reactor.future_tasks.drain_cancelled();
+            if let Some(local) = local {
+                local.drain_cancelled();
+            }
+        };
+        let poll_reactor = || {
+            let Some(waker) = reactor.future_tasks.pop_ready() else {
+                return false;
+            };
+            reactor.future_tasks.poll_task(waker);
+            true
+        };
+        let poll_local = || {
+            let Some((local, waker)) = local.and_then(|local| Some((local, local.pop_ready()?)))
+            else {
+                return false;
+            };
+            local.poll_task(waker);
+            true
+        };
+        let mut deadline = None;
         loop {
+            drain();
+            //local tasks have a queue of their own, which the reactor cannot see
+            let local_ready = local.is_some_and(LocalTasks::has_ready);
+            reactor.wait_for_io(if local_ready {
+                Some(Instant::now())
+            } else {
+                deadline
+            });
             reactor.update_timers();
-            reactor.wait_for_io();
-            while let Some(id) = READY_QUEUE.pop() {
-                reactor.drain_queue();
-                if let Entry::Occupied(mut entry) = reactor.future_tasks.write().unwrap().entry(id)
-                {
-                    let task_info = entry.get_mut();
-                    let task_ref = task_info.get_mut().unwrap();
-                    let waker = task_ref.waker.clone();
-                    let waker: Waker = waker.into();
-                    let mut context = Context::from_waker(&waker);
-                    let polling_state = task_ref.task.as_mut().poll(&mut context);
-                    if polling_state.is_ready() {
-                        entry.remove();
-                    }
+            for polled in 0..EVENT_INTERVAL {
+                drain();
+                //alternates which queue goes first so neither can starve the other
+                let local_first = polled % 2 == 1;
+                let polled_any = (local_first && poll_local())
+                    || poll_reactor()
+                    || (!local_first && poll_local());
+                if !polled_any {
+                    break;
+                }
+                if reactor.abort_requested.load(Ordering::Acquire) {
+                    panic!("a spawned task panicked and the runtime is configured to abort");
                 }
             }
 
-            if TIMERS.is_empty() && reactor.is_empty() {
+            //picks up tasks cancelled during the last pass
+            drain();
+            if !join_handle.is_finished() {
+                continue;
+            }
+            let is_empty = reactor.is_empty() && local.is_none_or(LocalTasks::is_empty);
+            let done = match shutdown {
+                Shutdown::Immediate => true,
+                Shutdown::WaitAll => is_empty,
+                Shutdown::Timeout(timeout) => {
+                    let deadline = *deadline.get_or_insert_with(|| Instant::now() + timeout);
+                    is_empty || Instant::now() >= deadline
+                }
+            };
+            if done {
                 break;
             }
         }
+        drop(shutdown_guard);
 
-        loop {
-            let mut context = Context::from_waker(Waker::noop());
-            if let std::task::Poll::Ready(result) = join_handle.poll_unpin(&mut context) {
-                return result;
+        let mut context = Context::from_waker(Waker::noop());
+        match join_handle.poll_unpin(&mut context) {
+            std::task::Poll::Ready(Ok(result)) => result,
+            std::task::Poll::Ready(Err(err)) => panic::resume_unwind(err.into_panic()),
+            std::task::Poll::Pending => {
+                unreachable!("the root task sends its result before finishing")
             }
         }
     }
+}
+
+//shuts the tasks down once the run loop exits, also when it unwinds under PanicPolicy::Abort,
+//so no task or abort request leaks into the next run
+struct ShutdownGuard<'a> {
+    reactor: &'a Reactor<'static>,
+    local: Option<&'a LocalTasks>,
+}
+
+impl Drop for ShutdownGuard<'_> {
+    fn drop(&mut self) {
+        self.reactor.future_tasks.shutdown();
+        if let Some(local) = self.local {
+            local.shutdown();
+        }
+        self.reactor.abort_requested.store(false, Ordering::Release);
+    }
+}
+
+impl Handle {
+    /// returns the handle of the runtime driving the current task.
+    ///
+    /// # Panics
+    /// Panics when called outside of [`Runtime::block_on`]
+    pub fn current() -> Self {
+        Self::try_current().expect("Handle::current() called outside of a runtime")
+    }
+
+    fn try_current() -> Option<Self> {
+        CURRENT.with(|current| current.borrow().clone())
+    }
 
     pub fn spawn<K: Send + 'static, F: Future<Output = K> + Send + 'static>(
+        &self,
         future: F,
     ) -> JoinHandle<K> {
-        println!("spawn code");
-        REACTOR.push_task(future)
+        self.reactor.push_task(future)
+    }
+
+    pub(crate) fn reactor(&self) -> &Reactor<'static> {
+        &self.reactor
+    }
+
+    //lets leaf futures reach the reactor from Drop without keeping it alive
+    pub(crate) fn downgrade(&self) -> Weak<Reactor<'static>> {
+        Arc::downgrade(&self.reactor)
+    }
+
+    fn enter(&self) -> EnterGuard {
+        let previous = CURRENT.with(|current| current.replace(Some(self.clone())));
+        EnterGuard { previous }
     }
 }
 
-#[derive(Debug)]
-struct FutureWaker {
-    id: Uuid,
+impl Drop for EnterGuard {
+    fn drop(&mut self) {
+        CURRENT.with(|current| *current.borrow_mut() = self.previous.take());
+    }
 }
 
-impl FutureWaker {
-    pub fn new(id: Uuid) -> Self {
-        Self { id }
+impl WasmRuntimeAsyncEngine {
+    /// function to execute futures on a fresh [`Runtime`]
+    pub fn block_on<K: Send + 'static, F: Future<Output = K> + Send + 'static>(future: F) -> K {
+        Runtime::new().block_on(future)
     }
-    fn wake_inner(&self) {
-        READY_QUEUE.push(self.id);
+
+    /// executes `future` on a fresh [`Runtime`] and waits for every spawned task as well
+    pub fn block_on_all<K: Send + 'static, F: Future<Output = K> + Send + 'static>(future: F) -> K {
+        Runtime::new().block_on_all(future)
     }
-}
 
-impl Wake for FutureWaker {
-    fn wake(self: std::sync::Arc<Self>) {
-        self.wake_inner();
+    pub fn spawn<K: Send + 'static, F: Future<Output = K> + Send + 'static>(
+        future: F,
+    ) -> JoinHandle<K> {
+        Handle::current().spawn(future)
     }
 }
 
@@ -227,6 +583,7 @@ impl Wake for FutureWaker {
 mod test {
 
     use super::*;
+    use crate::Timer;
     use std::future::Future;
 
     struct CountFuture {
@@ -252,27 +609,65 @@ mod test {
     }
 
     #[test]
-    fn test_enqueue() {
+    fn test_block_on() {
         let count_future = CountFuture { max: 3, min: 0 };
-        let reactor = Reactor::default();
-        let handle = reactor.push_task(async move {
-            count_future.await;
+
+        WasmRuntimeAsyncEngine::block_on(async move { assert_eq!(count_future.await, 3) });
+    }
+
+    #[test]
+    fn test_runtimes_are_isolated() {
+        let first = Runtime::new();
+        let second = Runtime::new();
+        let handle = first.handle();
+        first.block_on(async move {
+            assert!(Arc::ptr_eq(&Handle::current().reactor, &handle.reactor));
         });
-        reactor.drain_queue();
-        let mut future_task = reactor.future_tasks.write().unwrap();
-        let task = future_task.get_mut(&handle.id).unwrap();
-        let fut_waker = task.lock().unwrap().waker.clone();
-        let waker: Waker = fut_waker.into();
-        let count_future = &mut task.lock().unwrap().task;
-        let mut context = Context::from_waker(&waker);
-        futures::pin_mut!(count_future);
-        let _ = count_future.as_mut().poll(&mut context);
+        assert!(!Arc::ptr_eq(&first.handle.reactor, &second.handle.reactor));
+        assert!(second.handle.reactor.is_empty());
     }
 
     #[test]
-    fn test_block_on() {
-        let count_future = CountFuture { max: 3, min: 0 };
+    fn test_cancel_releases_timers() {
+        Runtime::new().block_on(async {
+            let handle = Handle::current().spawn(Timer::sleep(Duration::from_secs(10)));
+            Timer::sleep(Duration::from_millis(10)).await;
+            handle.cancel();
+            assert!(handle.await.unwrap_err().is_cancelled());
+            assert!(Handle::current().reactor.timers.lock().unwrap().is_empty());
+        });
+    }
 
-        WasmRuntimeAsyncEngine::block_on(async move { assert_eq!(count_future.await, 3) });
+    #[test]
+    fn test_timeout_releases_timers() {
+        Runtime::new().block_on(async {
+            let result = Timer::timeout(
+                Timer::sleep(Duration::from_secs(10)),
+                Duration::from_millis(10),
+            )
+            .await;
+            assert!(result.is_err());
+            assert!(Handle::current().reactor.timers.lock().unwrap().is_empty());
+        });
+    }
+
+    #[test]
+    fn test_interval_reuses_timer_entry() {
+        Runtime::new().block_on(async {
+            let mut interval = Timer::interval(Duration::from_millis(5));
+            let mut ticker = Handle::current().spawn(async move {
+                loop {
+                    interval.tick().await;
+                }
+            });
+            for _ in 0..5 {
+                Timer::sleep(Duration::from_millis(7)).await;
+                //the interval keeps at most its single entry next to ours
+                assert!(Handle::current().reactor.timers.lock().unwrap().len() <= 2);
+            }
+            ticker.cancel();
+            assert!((&mut ticker).await.unwrap_err().is_cancelled());
+            assert!(Handle::current().reactor.timers.lock().unwrap().is_empty());
+        });
     }
 }
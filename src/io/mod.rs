@@ -1,3 +1,5 @@
 pub mod net;
+pub mod pollable;
 pub mod timer;
-pub use timer::Timer;
+pub use pollable::{wait_for, PollableFuture};
+pub use timer::{Interval, MissedTickBehavior, Sleep, Timeout, Timer};
@@ -1,10 +1,20 @@
-use uuid::Uuid;
+#[cfg(any(feature = "futures-io", feature = "tokio-io"))]
+mod compat;
+mod dns;
+mod happy_eyeballs;
+mod options;
+mod split;
+mod udp;
+pub use dns::lookup_host;
+pub use options::TcpSocketBuilder;
+pub use split::{OwnedReadHalf, OwnedWriteHalf, ReadHalf, ReuniteError, WriteHalf};
+pub use udp::UdpSocket;
 
 use crate::{
     bindings::wasi::{
         io::{
             poll::Pollable,
-            streams::{InputStream, OutputStream},
+            streams::{InputStream, OutputStream, StreamError},
         },
         sockets::{
             instance_network::instance_network,
@@ -13,30 +23,58 @@ use crate::{
             tcp_create_socket::{create_tcp_socket, ErrorCode},
         },
     },
-    engine::REACTOR,
+    io::pollable::{wait_for, PollableFuture},
+    task,
 };
+use futures::Stream;
 use std::io::ErrorKind;
-use std::net::IpAddr;
-use std::{cell::OnceCell, future::Future, sync::Arc, task::Poll};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::{
+    future::{poll_fn, Future},
+    pin::Pin,
+    sync::Arc,
+    task::{ready, Context, Poll},
+};
 
 pub struct TcpStream {
-    socket: TcpSocket,
+    reader: Option<StreamReader>,
+    writer: Option<StreamWriter>,
+    socket: Arc<StreamSocket>,
+}
+//socket shared by a stream and its owned halves, shut down once the last of them is dropped.
+//The host refuses to drop a resource while its children are alive, so pollables and streams are
+//always declared before the resource they belong to, here and in every type holding them
+struct StreamSocket {
+    pollable: PollableRef,
+    tcp: TcpSocket,
+    network: Network,
+}
+//receiving side of a connected stream
+struct StreamReader {
+    //a read waiting for readiness keeps its registration here, so it survives being cancelled
+    ready: Option<PollableFuture>,
     pollable: PollableRef,
-    input_stream: OnceCell<InputStream>,
-    output_stream: OnceCell<OutputStream>,
+    stream: InputStream,
+}
+//sending side of a connected stream
+struct StreamWriter {
+    ready: Option<PollableFuture>,
+    //set between starting a flush and seeing it complete
+    flushing: bool,
+    pollable: PollableRef,
+    stream: OutputStream,
+}
+/// A TCP socket listening for incoming connections
+pub struct TcpListener {
+    pollable: PollableRef,
+    socket: TcpSocket,
     network: Network,
 }
+
 type IOResult<T> = std::io::Result<T>;
 type IOError = std::io::Error;
 type PollableRef = Arc<Pollable>;
 
-struct ConnectionFuture<'a> {
-    stream: &'a mut TcpStream,
-    async_key: String,
-    address: IpAddress,
-    port: u16,
-}
-
 impl TcpStream {
     pub fn new_ipv4() -> IOResult<Self> {
         Self::new_inner(IpAddressFamily::Ipv4)
@@ -46,54 +84,384 @@ impl TcpStream {
         Self::new_inner(IpAddressFamily::Ipv6)
     }
 
+    /// resolves `name` and connects to the first address that accepts the connection,
+    /// racing ipv6 and ipv4 addresses as [`TcpStream::connect_any`] does
+    pub async fn connect_host(name: &str, port: u16) -> IOResult<Self> {
+        let addresses = lookup_host(name).await?;
+        if addresses.is_empty() {
+            return Err(IOError::new(
+                ErrorKind::NotFound,
+                "host name resolved to no addresses",
+            ));
+        }
+        let addresses = addresses
+            .into_iter()
+            .map(|address| SocketAddr::new(address.into(), port));
+        Self::connect_any(addresses).await
+    }
+
     pub fn new_inner(address: IpAddressFamily) -> IOResult<Self> {
         let socket = create_tcp_socket(address)?;
+        Ok(Self::from_socket(socket))
+    }
+
+    //creates a socket of the matching address family and connects it
+    async fn open(address: SocketAddr) -> IOResult<Self> {
+        let mut stream = Self::new_inner(address_family(&address))?;
+        stream.connect(address).await?;
+        Ok(stream)
+    }
+
+    /// asynchronously connects to `address`, keeping the scope id and flow info of ipv6 addresses
+    pub async fn connect<A: Into<SocketAddr>>(&mut self, address: A) -> IOResult<()> {
+        self.start_connect(address.into().into())?;
+        let socket = &self.socket;
+        let (input, output) =
+            retry_would_block(&socket.pollable, || socket.tcp.finish_connect()).await?;
+        self.set_streams(input, output);
+        Ok(())
+    }
+
+    fn start_connect(&mut self, address: IpSocketAddress) -> IOResult<()> {
+        self.socket
+            .tcp
+            .start_connect(&self.socket.network, address)?;
+        Ok(())
+    }
+
+    /// address the socket is bound to
+    pub fn local_addr(&self) -> IOResult<SocketAddr> {
+        Ok(self.socket.tcp.local_address()?.into())
+    }
+
+    /// address of the connected peer
+    pub fn peer_addr(&self) -> IOResult<SocketAddr> {
+        Ok(self.socket.tcp.remote_address()?.into())
+    }
+
+    pub fn finish_connecting(&mut self) -> IOResult<()> {
+        let (input, output) = self.socket.tcp.finish_connect()?;
+        self.set_streams(input, output);
+        Ok(())
+    }
+
+    fn from_socket(socket: TcpSocket) -> Self {
         let pollable = socket.subscribe();
-        Ok(Self {
-            socket,
+        Self {
+            reader: None,
+            writer: None,
+            socket: Arc::new(StreamSocket {
+                pollable: Arc::new(pollable),
+                tcp: socket,
+                network: instance_network(),
+            }),
+        }
+    }
+
+    //wraps a socket handed out by TcpListener::accept, which is already connected
+    fn from_accepted(socket: TcpSocket, input: InputStream, output: OutputStream) -> Self {
+        let mut stream = Self::from_socket(socket);
+        stream.set_streams(input, output);
+        stream
+    }
+
+    fn set_streams(&mut self, input: InputStream, output: OutputStream) {
+        self.reader = Some(StreamReader {
+            ready: None,
+            pollable: Arc::new(input.subscribe()),
+            stream: input,
+        });
+        self.writer = Some(StreamWriter {
+            ready: None,
+            flushing: false,
+            pollable: Arc::new(output.subscribe()),
+            stream: output,
+        });
+    }
+
+    /// splits the stream into halves that can be moved to different tasks, so one can read while the other writes.
+    /// The socket is shut down once both halves are dropped, or they can be put back together with `reunite`
+    pub fn into_split(self) -> (OwnedReadHalf, OwnedWriteHalf) {
+        split::owned(self.reader, self.writer, self.socket)
+    }
+
+    /// splits the stream into borrowed halves that can be used concurrently within one task
+    pub fn split(&mut self) -> (ReadHalf<'_>, WriteHalf<'_>) {
+        split::borrowed(self.reader.as_mut(), self.writer.as_mut(), &self.socket)
+    }
+
+    /// reads some bytes into `buf`, waiting until at least one byte is available.
+    /// Returns `Ok(0)` once the peer has closed the stream
+    pub async fn read(&mut self, buf: &mut [u8]) -> IOResult<usize> {
+        connected(self.reader.as_mut())?.read(buf).await
+    }
+
+    /// poll form of [`TcpStream::read`], registering the input pollable while no bytes are available
+    pub fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<IOResult<usize>> {
+        match self.reader.as_mut() {
+            Some(reader) => reader.poll_read(cx, buf),
+            None => Poll::Ready(Err(not_connected())),
+        }
+    }
+
+    /// reads exactly enough bytes to fill `buf`
+    pub async fn read_exact(&mut self, buf: &mut [u8]) -> IOResult<()> {
+        connected(self.reader.as_mut())?.read_exact(buf).await
+    }
+
+    /// writes as much of `buf` as the stream currently permits, waiting for capacity if needed.
+    /// Returns `Ok(0)` once the stream has been closed
+    pub async fn write(&mut self, buf: &[u8]) -> IOResult<usize> {
+        connected(self.writer.as_mut())?.write(buf).await
+    }
+
+    /// poll form of [`TcpStream::write`], registering the output pollable while there is no capacity
+    pub fn poll_write(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IOResult<usize>> {
+        match self.writer.as_mut() {
+            Some(writer) => writer.poll_write(cx, buf),
+            None => Poll::Ready(Err(not_connected())),
+        }
+    }
+
+    /// writes the whole of `buf`
+    pub async fn write_all(&mut self, buf: &[u8]) -> IOResult<()> {
+        connected(self.writer.as_mut())?.write_all(buf).await
+    }
+
+    /// waits until everything written so far has been handed over to the host
+    pub async fn flush(&mut self) -> IOResult<()> {
+        connected(self.writer.as_mut())?.flush().await
+    }
+
+    /// poll form of [`TcpStream::flush`]
+    pub fn poll_flush(&mut self, cx: &mut Context<'_>) -> Poll<IOResult<()>> {
+        match self.writer.as_mut() {
+            Some(writer) => writer.poll_flush(cx),
+            None => Poll::Ready(Err(not_connected())),
+        }
+    }
+
+    /// flushes and then shuts down the sending side, the peer reads end of stream
+    pub fn poll_shutdown(&mut self, cx: &mut Context<'_>) -> Poll<IOResult<()>> {
+        match self.writer.as_mut() {
+            Some(writer) => writer.poll_shutdown(cx, &self.socket.tcp),
+            None => Poll::Ready(Err(not_connected())),
+        }
+    }
+}
+
+impl StreamReader {
+    async fn read(&mut self, buf: &mut [u8]) -> IOResult<usize> {
+        poll_fn(|cx| self.poll_read(cx, buf)).await
+    }
+
+    fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<IOResult<usize>> {
+        //charged even when bytes are ready, so draining a fast stream still yields
+        ready!(task::poll_budget(cx));
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        loop {
+            if let Some(ready) = &mut self.ready {
+                ready!(Pin::new(ready).poll(cx));
+                self.ready = None;
+            }
+            match self.stream.read(buf.len() as u64) {
+                Ok(bytes) if bytes.is_empty() => self.ready = Some(wait_for(self.pollable.clone())),
+                Ok(bytes) => {
+                    buf[..bytes.len()].copy_from_slice(&bytes);
+                    return Poll::Ready(Ok(bytes.len()));
+                }
+                Err(StreamError::Closed) => return Poll::Ready(Ok(0)),
+                Err(error) => return Poll::Ready(Err(error.into())),
+            }
+        }
+    }
+
+    async fn read_exact(&mut self, mut buf: &mut [u8]) -> IOResult<()> {
+        while !buf.is_empty() {
+            match self.read(buf).await? {
+                0 => {
+                    return Err(IOError::new(
+                        ErrorKind::UnexpectedEof,
+                        "failed to fill whole buffer",
+                    ))
+                }
+                read => buf = &mut buf[read..],
+            }
+        }
+        Ok(())
+    }
+}
+
+impl StreamWriter {
+    async fn write(&mut self, buf: &[u8]) -> IOResult<usize> {
+        poll_fn(|cx| self.poll_write(cx, buf)).await
+    }
+
+    fn poll_write(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IOResult<usize>> {
+        ready!(task::poll_budget(cx));
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        loop {
+            ready!(self.poll_ready(cx));
+            match self.stream.check_write() {
+                Ok(0) => self.ready = Some(wait_for(self.pollable.clone())),
+                Ok(permit) => {
+                    let len = buf.len().min(permit as usize);
+                    self.stream.write(&buf[..len]).map_err(IOError::from)?;
+                    return Poll::Ready(Ok(len));
+                }
+                Err(StreamError::Closed) => return Poll::Ready(Ok(0)),
+                Err(error) => return Poll::Ready(Err(error.into())),
+            }
+        }
+    }
+
+    async fn write_all(&mut self, mut buf: &[u8]) -> IOResult<()> {
+        while !buf.is_empty() {
+            match self.write(buf).await? {
+                0 => {
+                    return Err(IOError::new(
+                        ErrorKind::WriteZero,
+                        "failed to write whole buffer",
+                    ))
+                }
+                written => buf = &buf[written..],
+            }
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> IOResult<()> {
+        poll_fn(|cx| self.poll_flush(cx)).await
+    }
+
+    fn poll_flush(&mut self, cx: &mut Context<'_>) -> Poll<IOResult<()>> {
+        ready!(task::poll_budget(cx));
+        if !self.flushing {
+            self.stream.flush()?;
+            self.flushing = true;
+        }
+        loop {
+            ready!(self.poll_ready(cx));
+            //check_write reports no capacity until the flush completes
+            match self.stream.check_write() {
+                Ok(0) => self.ready = Some(wait_for(self.pollable.clone())),
+                result => {
+                    self.flushing = false;
+                    return Poll::Ready(result.map(|_| ()).map_err(IOError::from));
+                }
+            }
+        }
+    }
+
+    fn poll_shutdown(&mut self, cx: &mut Context<'_>, socket: &TcpSocket) -> Poll<IOResult<()>> {
+        ready!(self.poll_flush(cx))?;
+        socket.shutdown(ShutdownType::Send)?;
+        Poll::Ready(Ok(()))
+    }
+
+    //waits out a pending registration on the output pollable
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if let Some(ready) = &mut self.ready {
+            ready!(Pin::new(ready).poll(cx));
+            self.ready = None;
+        }
+        Poll::Ready(())
+    }
+}
+
+impl TcpListener {
+    /// binds a socket to the address and starts listening for incoming connections
+    pub async fn bind<A: Into<SocketAddr>>(address: A) -> IOResult<Self> {
+        let address = address.into();
+        let socket = create_tcp_socket(address_family(&address))?;
+        Self::listen(socket, address, None).await
+    }
+
+    async fn listen(
+        socket: TcpSocket,
+        address: SocketAddr,
+        backlog_size: Option<u64>,
+    ) -> IOResult<Self> {
+        let pollable = socket.subscribe();
+        let listener = Self {
             pollable: Arc::new(pollable),
-            input_stream: OnceCell::new(),
-            output_stream: OnceCell::new(),
+            socket,
             network: instance_network(),
+        };
+        listener
+            .socket
+            .start_bind(&listener.network, address.into())?;
+        retry_would_block(&listener.pollable, || listener.socket.finish_bind()).await?;
+        if let Some(size) = backlog_size {
+            listener.set_listen_backlog_size(size)?;
+        }
+        listener.socket.start_listen()?;
+        retry_would_block(&listener.pollable, || listener.socket.finish_listen()).await?;
+        Ok(listener)
+    }
+
+    /// address the listener is bound to
+    pub fn local_addr(&self) -> IOResult<SocketAddr> {
+        Ok(self.socket.local_address()?.into())
+    }
+
+    /// waits for the next incoming connection and returns it along with the peer address
+    pub async fn accept(&self) -> IOResult<(TcpStream, SocketAddr)> {
+        let (socket, input, output) =
+            retry_would_block(&self.pollable, || self.socket.accept()).await?;
+        let peer = socket.remote_address()?.into();
+        Ok((TcpStream::from_accepted(socket, input, output), peer))
+    }
+
+    /// stream of incoming connections, accepting one per item
+    pub fn incoming(&self) -> impl Stream<Item = IOResult<TcpStream>> + '_ {
+        futures::stream::unfold(self, |listener| async move {
+            let stream = listener.accept().await.map(|(stream, _)| stream);
+            Some((stream, listener))
         })
     }
-    //asynchronously connects to the ip address
-    pub async fn connect<T: Into<IpAddress>>(&mut self, address: T, port: u16) -> IOResult<()> {
-        let connect_future = ConnectionFuture {
-            stream: self,
-            async_key: format!("socket-connection={}", Uuid::new_v4()),
-            address: address.into(),
-            port,
-        };
-        connect_future.await
-    }
-
-    fn start_connect<T: Into<IpAddress>>(&mut self, address: T, port: u16) -> IOResult<()> {
-        let ip_address: IpAddress = address.into();
-        let socket_address = match ip_address {
-            IpAddress::Ipv4(address) => IpSocketAddress::Ipv4(Ipv4SocketAddress { port, address }),
-            IpAddress::Ipv6(address) => IpSocketAddress::Ipv6(Ipv6SocketAddress {
-                port,
-                address,
-                scope_id: 0,  //need to put the right details here
-                flow_info: 0, // need to put the right details here
-            }),
-        };
-        self.socket.start_connect(&self.network, socket_address)?;
-        Ok(())
+}
+
+//retries a non-blocking socket operation until it stops reporting WouldBlock
+async fn retry_would_block<K>(
+    pollable: &PollableRef,
+    mut operation: impl FnMut() -> Result<K, ErrorCode>,
+) -> IOResult<K> {
+    loop {
+        task::consume_budget().await;
+        match operation() {
+            Err(ErrorCode::WouldBlock) => wait_until_ready(pollable).await,
+            result => return result.map_err(IOError::from),
+        }
     }
+}
 
-    pub fn finish_connecting(&mut self) -> IOResult<()> {
-        let (input, output) = self.socket.finish_connect()?;
-        let _ = self.input_stream.set(input);
-        let _ = self.output_stream.set(output);
-        Ok(())
+fn address_family(address: &SocketAddr) -> IpAddressFamily {
+    match address {
+        SocketAddr::V4(_) => IpAddressFamily::Ipv4,
+        SocketAddr::V6(_) => IpAddressFamily::Ipv6,
     }
 }
 
-impl Drop for TcpStream {
+fn not_connected() -> IOError {
+    IOError::new(ErrorKind::NotConnected, "socket is not connected")
+}
+
+fn connected<T>(side: Option<T>) -> IOResult<T> {
+    side.ok_or_else(not_connected)
+}
+
+async fn wait_until_ready(pollable: &PollableRef) {
+    wait_for(pollable.clone()).await
+}
+
+impl Drop for StreamSocket {
     fn drop(&mut self) {
-        let _ = self.socket.shutdown(ShutdownType::Both);
+        let _ = self.tcp.shutdown(ShutdownType::Both);
     }
 }
 
@@ -121,6 +489,76 @@ impl From<IpAddr> for IpAddress {
     }
 }
 
+impl From<IpAddress> for IpAddr {
+    fn from(address: IpAddress) -> Self {
+        match address {
+            IpAddress::Ipv4((a, b, c, d)) => IpAddr::V4(Ipv4Addr::new(a, b, c, d)),
+            IpAddress::Ipv6((a, b, c, d, e, f, g, h)) => {
+                IpAddr::V6(Ipv6Addr::new(a, b, c, d, e, f, g, h))
+            }
+        }
+    }
+}
+
+impl From<SocketAddrV4> for Ipv4SocketAddress {
+    fn from(address: SocketAddrV4) -> Self {
+        let [a, b, c, d] = address.ip().octets();
+        Ipv4SocketAddress {
+            port: address.port(),
+            address: (a, b, c, d),
+        }
+    }
+}
+
+impl From<Ipv4SocketAddress> for SocketAddrV4 {
+    fn from(address: Ipv4SocketAddress) -> Self {
+        let (a, b, c, d) = address.address;
+        SocketAddrV4::new(Ipv4Addr::new(a, b, c, d), address.port)
+    }
+}
+
+impl From<SocketAddrV6> for Ipv6SocketAddress {
+    fn from(address: SocketAddrV6) -> Self {
+        let [a, b, c, d, e, f, g, h] = address.ip().segments();
+        Ipv6SocketAddress {
+            port: address.port(),
+            flow_info: address.flowinfo(),
+            address: (a, b, c, d, e, f, g, h),
+            scope_id: address.scope_id(),
+        }
+    }
+}
+
+impl From<Ipv6SocketAddress> for SocketAddrV6 {
+    fn from(address: Ipv6SocketAddress) -> Self {
+        let (a, b, c, d, e, f, g, h) = address.address;
+        SocketAddrV6::new(
+            Ipv6Addr::new(a, b, c, d, e, f, g, h),
+            address.port,
+            address.flow_info,
+            address.scope_id,
+        )
+    }
+}
+
+impl From<SocketAddr> for IpSocketAddress {
+    fn from(address: SocketAddr) -> Self {
+        match address {
+            SocketAddr::V4(v4) => IpSocketAddress::Ipv4(v4.into()),
+            SocketAddr::V6(v6) => IpSocketAddress::Ipv6(v6.into()),
+        }
+    }
+}
+
+impl From<IpSocketAddress> for SocketAddr {
+    fn from(address: IpSocketAddress) -> Self {
+        match address {
+            IpSocketAddress::Ipv4(v4) => SocketAddr::V4(v4.into()),
+            IpSocketAddress::Ipv6(v6) => SocketAddr::V6(v6.into()),
+        }
+    }
+}
+
 impl From<ErrorCode> for IOError {
     fn from(address: ErrorCode) -> Self {
         let kind = (&address).into();
@@ -128,6 +566,19 @@ impl From<ErrorCode> for IOError {
     }
 }
 
+impl From<StreamError> for IOError {
+    fn from(error: StreamError) -> Self {
+        match error {
+            StreamError::Closed => IOError::new(ErrorKind::BrokenPipe, "stream is closed"),
+            StreamError::LastOperationFailed(error) => IOError::other(error.to_debug_string()),
+        }
+    }
+}
+
+//the resolver errors get kinds of their own: `NotFound` means the name does not exist,
+//`ResourceBusy` that the lookup may succeed when retried and `Other` that it will keep failing.
+//Codes without a matching kind map to `Other`, the error keeps the ErrorCode as its source
+//so callers can still tell them apart with `get_ref()` and `downcast_ref::<ErrorCode>()`
 impl From<&ErrorCode> for ErrorKind {
     fn from(address: &ErrorCode) -> Self {
         match address {
@@ -144,39 +595,78 @@ impl From<&ErrorCode> for ErrorKind {
             ErrorCode::NewSocketLimit => ErrorKind::Other,
             ErrorCode::AddressNotBindable => ErrorKind::Other,
             ErrorCode::AddressInUse => ErrorKind::AddrInUse,
-            ErrorCode::RemoteUnreachable => ErrorKind::NotFound,
+            ErrorCode::RemoteUnreachable => ErrorKind::HostUnreachable,
             ErrorCode::ConnectionRefused => ErrorKind::ConnectionRefused,
             ErrorCode::ConnectionReset => ErrorKind::ConnectionReset,
             ErrorCode::ConnectionAborted => ErrorKind::ConnectionAborted,
             ErrorCode::DatagramTooLarge => ErrorKind::Other,
-            ErrorCode::NameUnresolvable => ErrorKind::Other,
-            ErrorCode::TemporaryResolverFailure => ErrorKind::Other,
+            ErrorCode::NameUnresolvable => ErrorKind::NotFound,
+            ErrorCode::TemporaryResolverFailure => ErrorKind::ResourceBusy,
             ErrorCode::PermanentResolverFailure => ErrorKind::Other,
         }
     }
 }
 
-impl<'a> Future for ConnectionFuture<'a> {
-    type Output = IOResult<()>;
-    fn poll(
-        self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
-    ) -> std::task::Poll<Self::Output> {
-        let this = self.get_mut();
-        if !REACTOR.is_pollable(&this.async_key) {
-            this.stream.start_connect(this.address, this.port)?;
-            REACTOR.register(
-                this.async_key.clone(),
-                (this.stream.pollable.clone(), cx.waker().clone()),
-            );
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_code(error: &IOError) -> Option<ErrorCode> {
+        error.get_ref()?.downcast_ref::<ErrorCode>().copied()
+    }
+
+    #[test]
+    fn test_resolver_errors_keep_their_code() {
+        for (code, kind) in [
+            (ErrorCode::NameUnresolvable, ErrorKind::NotFound),
+            (ErrorCode::TemporaryResolverFailure, ErrorKind::ResourceBusy),
+            (ErrorCode::PermanentResolverFailure, ErrorKind::Other),
+        ] {
+            let error = IOError::from(code);
+            assert_eq!(error.kind(), kind);
+            assert_eq!(error_code(&error), Some(code));
+            assert!(error.to_string().contains(code.name()));
         }
+    }
 
-        //A PLACE TO CHECK IF THE REACTOR UPDATED THIS KEY
-        if REACTOR.check_ready(&this.async_key) {
-            this.stream.finish_connecting()?;
-            Poll::Ready(Ok(()))
-        } else {
-            Poll::Pending
+    #[test]
+    fn test_datagram_too_large_keeps_its_code() {
+        let error = IOError::from(ErrorCode::DatagramTooLarge);
+        assert_eq!(error_code(&error), Some(ErrorCode::DatagramTooLarge));
+        assert!(error.to_string().contains("datagram-too-large"));
+    }
+
+    #[test]
+    fn test_socket_address_round_trip() {
+        let v4: SocketAddr = "192.168.1.20:8080".parse().unwrap();
+        let converted = IpSocketAddress::from(v4);
+        assert!(matches!(
+            converted,
+            IpSocketAddress::Ipv4(Ipv4SocketAddress {
+                port: 8080,
+                address: (192, 168, 1, 20)
+            })
+        ));
+        assert_eq!(SocketAddr::from(converted), v4);
+
+        let v6 = SocketAddr::V6(SocketAddrV6::new(
+            "fe80::1:2".parse().unwrap(),
+            443,
+            0x12345,
+            7,
+        ));
+        let converted = IpSocketAddress::from(v6);
+        match converted {
+            IpSocketAddress::Ipv6(address) => {
+                assert_eq!(address.scope_id, 7);
+                assert_eq!(address.flow_info, 0x12345);
+                assert_eq!(address.address, (0xfe80, 0, 0, 0, 0, 0, 1, 2));
+            }
+            IpSocketAddress::Ipv4(_) => panic!("expected an ipv6 address"),
         }
+        assert_eq!(SocketAddr::from(converted), v6);
+
+        let ip: IpAddr = "2001:db8::8".parse().unwrap();
+        assert_eq!(IpAddr::from(IpAddress::from(ip)), ip);
     }
 }
@@ -0,0 +1,71 @@
+//byte stream traits of the ecosystem, implemented on top of the poll methods of the stream types
+use super::{IOResult, OwnedReadHalf, OwnedWriteHalf, ReadHalf, TcpStream, WriteHalf};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+macro_rules! impl_async_read {
+    ($($stream:ty),*) => {$(
+        #[cfg(feature = "futures-io")]
+        impl futures::io::AsyncRead for $stream {
+            fn poll_read(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+                buf: &mut [u8],
+            ) -> Poll<IOResult<usize>> {
+                self.get_mut().poll_read(cx, buf)
+            }
+        }
+
+        #[cfg(feature = "tokio-io")]
+        impl tokio::io::AsyncRead for $stream {
+            fn poll_read(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+                buf: &mut tokio::io::ReadBuf<'_>,
+            ) -> Poll<IOResult<()>> {
+                let read = std::task::ready!(self.get_mut().poll_read(cx, buf.initialize_unfilled()))?;
+                buf.advance(read);
+                Poll::Ready(Ok(()))
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_async_write {
+    ($($stream:ty),*) => {$(
+        #[cfg(feature = "futures-io")]
+        impl futures::io::AsyncWrite for $stream {
+            fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IOResult<usize>> {
+                self.get_mut().poll_write(cx, buf)
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IOResult<()>> {
+                self.get_mut().poll_flush(cx)
+            }
+
+            fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IOResult<()>> {
+                self.get_mut().poll_shutdown(cx)
+            }
+        }
+
+        #[cfg(feature = "tokio-io")]
+        impl tokio::io::AsyncWrite for $stream {
+            fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IOResult<usize>> {
+                self.get_mut().poll_write(cx, buf)
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IOResult<()>> {
+                self.get_mut().poll_flush(cx)
+            }
+
+            fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IOResult<()>> {
+                self.get_mut().poll_shutdown(cx)
+            }
+        }
+    )*};
+}
+
+impl_async_read!(TcpStream, OwnedReadHalf, ReadHalf<'_>);
+impl_async_write!(TcpStream, OwnedWriteHalf, WriteHalf<'_>);
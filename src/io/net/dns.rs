@@ -0,0 +1,19 @@
+use super::{retry_would_block, IOResult};
+use crate::bindings::wasi::sockets::{
+    instance_network::instance_network, ip_name_lookup::resolve_addresses, network::IpAddress,
+};
+use std::sync::Arc;
+
+/// resolves a host name to the list of addresses it points to
+pub async fn lookup_host(name: &str) -> IOResult<Vec<IpAddress>> {
+    let network = instance_network();
+    let stream = resolve_addresses(&network, name)?;
+    //declared after the stream so it is dropped first
+    let pollable = Arc::new(stream.subscribe());
+    let mut addresses = Vec::new();
+    while let Some(address) = retry_would_block(&pollable, || stream.resolve_next_address()).await?
+    {
+        addresses.push(address);
+    }
+    Ok(addresses)
+}
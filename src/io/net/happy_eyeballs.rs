@@ -0,0 +1,84 @@
+use super::{IOError, IOResult, TcpStream};
+use crate::Timer;
+use futures::{
+    future::{select, Either},
+    stream::FuturesUnordered,
+    StreamExt,
+};
+use std::{io::ErrorKind, net::SocketAddr, time::Duration};
+
+//recommended "Connection Attempt Delay" of RFC 8305
+const CONNECTION_ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+impl TcpStream {
+    /// connects to the first of `addresses` that accepts the connection, racing them as in
+    /// Happy Eyeballs (RFC 8305): ipv6 and ipv4 addresses are tried alternately, starting with ipv6,
+    /// and a new attempt starts every 250ms or as soon as the previous one fails.
+    /// The attempts that lose the race are cancelled
+    pub async fn connect_any<I: IntoIterator<Item = SocketAddr>>(addresses: I) -> IOResult<Self> {
+        let mut addresses = interleave_families(addresses).into_iter();
+        let mut attempts = FuturesUnordered::new();
+        let mut last_error = None;
+        loop {
+            if attempts.is_empty() {
+                match addresses.next() {
+                    Some(address) => attempts.push(Self::open(address)),
+                    None => break,
+                }
+            }
+            //None when the delay ran out before any attempt finished
+            let finished = if addresses.len() == 0 {
+                attempts.next().await
+            } else {
+                match select(attempts.next(), Timer::sleep(CONNECTION_ATTEMPT_DELAY)).await {
+                    Either::Left((finished, _)) => finished,
+                    Either::Right(_) => None,
+                }
+            };
+            match finished {
+                Some(Ok(stream)) => return Ok(stream),
+                Some(Err(error)) => last_error = Some(error),
+                None => {}
+            }
+            if let Some(address) = addresses.next() {
+                attempts.push(Self::open(address));
+            }
+        }
+        Err(last_error
+            .unwrap_or_else(|| IOError::new(ErrorKind::InvalidInput, "no addresses to connect to")))
+    }
+}
+
+//orders the addresses ipv6 first and then alternating between the families, keeping the order within a family
+fn interleave_families<I: IntoIterator<Item = SocketAddr>>(addresses: I) -> Vec<SocketAddr> {
+    let (ipv6, ipv4): (Vec<_>, Vec<_>) = addresses.into_iter().partition(SocketAddr::is_ipv6);
+    let (mut ipv6, mut ipv4) = (ipv6.into_iter(), ipv4.into_iter());
+    let mut ordered = Vec::with_capacity(ipv6.len() + ipv4.len());
+    loop {
+        match (ipv6.next(), ipv4.next()) {
+            (None, None) => return ordered,
+            (first, second) => ordered.extend(first.into_iter().chain(second)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interleave_families() {
+        let addresses: Vec<SocketAddr> = ["10.0.0.1:80", "10.0.0.2:80", "[::1]:80", "10.0.0.3:80"]
+            .iter()
+            .map(|address| address.parse().unwrap())
+            .collect();
+        let ordered: Vec<String> = interleave_families(addresses)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            ordered,
+            ["[::1]:80", "10.0.0.1:80", "10.0.0.2:80", "10.0.0.3:80"]
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use super::{address_family, IOResult, TcpListener, TcpStream};
+use crate::bindings::wasi::sockets::{tcp::TcpSocket, tcp_create_socket::create_tcp_socket};
+use std::{net::SocketAddr, time::Duration};
+
+/// Configures a TCP socket before it connects or starts listening
+#[derive(Debug, Clone, Default)]
+pub struct TcpSocketBuilder {
+    keep_alive: Option<bool>,
+    keep_alive_idle_time: Option<Duration>,
+    keep_alive_interval: Option<Duration>,
+    keep_alive_count: Option<u32>,
+    hop_limit: Option<u8>,
+    receive_buffer_size: Option<u64>,
+    send_buffer_size: Option<u64>,
+    listen_backlog_size: Option<u64>,
+}
+
+impl TcpSocketBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// enables or disables keepalive probes on the connection
+    pub fn keep_alive(mut self, enabled: bool) -> Self {
+        self.keep_alive = Some(enabled);
+        self
+    }
+
+    /// time the connection has to be idle before the first keepalive probe is sent
+    pub fn keep_alive_idle_time(mut self, idle_time: Duration) -> Self {
+        self.keep_alive_idle_time = Some(idle_time);
+        self
+    }
+
+    /// time between keepalive probes
+    pub fn keep_alive_interval(mut self, interval: Duration) -> Self {
+        self.keep_alive_interval = Some(interval);
+        self
+    }
+
+    /// number of unanswered keepalive probes before the connection is dropped
+    pub fn keep_alive_count(mut self, count: u32) -> Self {
+        self.keep_alive_count = Some(count);
+        self
+    }
+
+    /// hop limit (ttl) of outgoing packets
+    pub fn hop_limit(mut self, hop_limit: u8) -> Self {
+        self.hop_limit = Some(hop_limit);
+        self
+    }
+
+    pub fn receive_buffer_size(mut self, size: u64) -> Self {
+        self.receive_buffer_size = Some(size);
+        self
+    }
+
+    pub fn send_buffer_size(mut self, size: u64) -> Self {
+        self.send_buffer_size = Some(size);
+        self
+    }
+
+    /// maximum number of pending connections, only used by `listen`
+    pub fn listen_backlog_size(mut self, size: u64) -> Self {
+        self.listen_backlog_size = Some(size);
+        self
+    }
+
+    /// creates a socket with these options and connects it to the address
+    pub async fn connect<A: Into<SocketAddr>>(self, address: A) -> IOResult<TcpStream> {
+        let address = address.into();
+        let mut stream = TcpStream::new_inner(address_family(&address))?;
+        self.apply(&stream.socket.tcp)?;
+        stream.connect(address).await?;
+        Ok(stream)
+    }
+
+    /// creates a socket with these options, binds it to the address and starts listening
+    pub async fn listen<A: Into<SocketAddr>>(self, address: A) -> IOResult<TcpListener> {
+        let address = address.into();
+        let socket = create_tcp_socket(address_family(&address))?;
+        self.apply(&socket)?;
+        TcpListener::listen(socket, address, self.listen_backlog_size).await
+    }
+
+    fn apply(&self, socket: &TcpSocket) -> IOResult<()> {
+        if let Some(enabled) = self.keep_alive {
+            socket.set_keep_alive_enabled(enabled)?;
+        }
+        if let Some(idle_time) = self.keep_alive_idle_time {
+            socket.set_keep_alive_idle_time(to_nanos(idle_time))?;
+        }
+        if let Some(interval) = self.keep_alive_interval {
+            socket.set_keep_alive_interval(to_nanos(interval))?;
+        }
+        if let Some(count) = self.keep_alive_count {
+            socket.set_keep_alive_count(count)?;
+        }
+        if let Some(hop_limit) = self.hop_limit {
+            socket.set_hop_limit(hop_limit)?;
+        }
+        if let Some(size) = self.receive_buffer_size {
+            socket.set_receive_buffer_size(size)?;
+        }
+        if let Some(size) = self.send_buffer_size {
+            socket.set_send_buffer_size(size)?;
+        }
+        Ok(())
+    }
+}
+
+//getters and setters shared by the stream and the listener, `$socket` is the path to their TcpSocket.
+//Options set on a listener are inherited by the connections it accepts
+macro_rules! impl_socket_options {
+    ($type:ty, $($socket:ident).+) => {
+        impl $type {
+            pub fn keep_alive(&self) -> IOResult<bool> {
+                Ok(self.$($socket).+.keep_alive_enabled()?)
+            }
+
+            /// enables or disables keepalive probes on the connection
+            pub fn set_keep_alive(&self, enabled: bool) -> IOResult<()> {
+                Ok(self.$($socket).+.set_keep_alive_enabled(enabled)?)
+            }
+
+            pub fn keep_alive_idle_time(&self) -> IOResult<Duration> {
+                Ok(Duration::from_nanos(self.$($socket).+.keep_alive_idle_time()?))
+            }
+
+            /// time the connection has to be idle before the first keepalive probe is sent
+            pub fn set_keep_alive_idle_time(&self, idle_time: Duration) -> IOResult<()> {
+                Ok(self.$($socket).+.set_keep_alive_idle_time(to_nanos(idle_time))?)
+            }
+
+            pub fn keep_alive_interval(&self) -> IOResult<Duration> {
+                Ok(Duration::from_nanos(self.$($socket).+.keep_alive_interval()?))
+            }
+
+            /// time between keepalive probes
+            pub fn set_keep_alive_interval(&self, interval: Duration) -> IOResult<()> {
+                Ok(self.$($socket).+.set_keep_alive_interval(to_nanos(interval))?)
+            }
+
+            pub fn keep_alive_count(&self) -> IOResult<u32> {
+                Ok(self.$($socket).+.keep_alive_count()?)
+            }
+
+            /// number of unanswered keepalive probes before the connection is dropped
+            pub fn set_keep_alive_count(&self, count: u32) -> IOResult<()> {
+                Ok(self.$($socket).+.set_keep_alive_count(count)?)
+            }
+
+            pub fn hop_limit(&self) -> IOResult<u8> {
+                Ok(self.$($socket).+.hop_limit()?)
+            }
+
+            /// hop limit (ttl) of outgoing packets
+            pub fn set_hop_limit(&self, hop_limit: u8) -> IOResult<()> {
+                Ok(self.$($socket).+.set_hop_limit(hop_limit)?)
+            }
+
+            pub fn receive_buffer_size(&self) -> IOResult<u64> {
+                Ok(self.$($socket).+.receive_buffer_size()?)
+            }
+
+            pub fn set_receive_buffer_size(&self, size: u64) -> IOResult<()> {
+                Ok(self.$($socket).+.set_receive_buffer_size(size)?)
+            }
+
+            pub fn send_buffer_size(&self) -> IOResult<u64> {
+                Ok(self.$($socket).+.send_buffer_size()?)
+            }
+
+            pub fn set_send_buffer_size(&self, size: u64) -> IOResult<()> {
+                Ok(self.$($socket).+.set_send_buffer_size(size)?)
+            }
+        }
+    };
+}
+
+impl_socket_options!(TcpStream, socket.tcp);
+impl_socket_options!(TcpListener, socket);
+
+impl TcpListener {
+    /// maximum number of pending connections
+    pub fn set_listen_backlog_size(&self, size: u64) -> IOResult<()> {
+        Ok(self.socket.set_listen_backlog_size(size)?)
+    }
+}
+
+//wasi durations are in nanoseconds
+fn to_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
@@ -0,0 +1,208 @@
+use super::{
+    connected, not_connected, IOResult, StreamReader, StreamSocket, StreamWriter, TcpStream,
+};
+use std::{
+    error::Error,
+    fmt,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+/// Owned reading half of a [`TcpStream`], created by [`TcpStream::into_split`]
+pub struct OwnedReadHalf {
+    reader: Option<StreamReader>,
+    socket: Arc<StreamSocket>,
+}
+
+/// Owned writing half of a [`TcpStream`], created by [`TcpStream::into_split`]
+pub struct OwnedWriteHalf {
+    writer: Option<StreamWriter>,
+    socket: Arc<StreamSocket>,
+}
+
+/// Borrowed reading half of a [`TcpStream`], created by [`TcpStream::split`]
+pub struct ReadHalf<'a> {
+    reader: Option<&'a mut StreamReader>,
+}
+
+/// Borrowed writing half of a [`TcpStream`], created by [`TcpStream::split`]
+pub struct WriteHalf<'a> {
+    writer: Option<&'a mut StreamWriter>,
+    socket: &'a StreamSocket,
+}
+
+/// Error returned by `reunite` when the halves came from different streams, handing both back
+pub struct ReuniteError(pub OwnedReadHalf, pub OwnedWriteHalf);
+
+pub(super) fn owned(
+    reader: Option<StreamReader>,
+    writer: Option<StreamWriter>,
+    socket: Arc<StreamSocket>,
+) -> (OwnedReadHalf, OwnedWriteHalf) {
+    let read = OwnedReadHalf {
+        reader,
+        socket: socket.clone(),
+    };
+    (read, OwnedWriteHalf { writer, socket })
+}
+
+pub(super) fn borrowed<'a>(
+    reader: Option<&'a mut StreamReader>,
+    writer: Option<&'a mut StreamWriter>,
+    socket: &'a StreamSocket,
+) -> (ReadHalf<'a>, WriteHalf<'a>) {
+    (ReadHalf { reader }, WriteHalf { writer, socket })
+}
+
+impl OwnedReadHalf {
+    /// puts the stream back together, failing if `write` belongs to a different stream
+    pub fn reunite(self, write: OwnedWriteHalf) -> Result<TcpStream, ReuniteError> {
+        if !Arc::ptr_eq(&self.socket, &write.socket) {
+            return Err(ReuniteError(self, write));
+        }
+        Ok(TcpStream {
+            reader: self.reader,
+            writer: write.writer,
+            socket: self.socket,
+        })
+    }
+
+    /// reads some bytes into `buf`, see [`TcpStream::read`]
+    pub async fn read(&mut self, buf: &mut [u8]) -> IOResult<usize> {
+        connected(self.reader.as_mut())?.read(buf).await
+    }
+
+    /// poll form of [`OwnedReadHalf::read`]
+    pub fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<IOResult<usize>> {
+        match self.reader.as_mut() {
+            Some(reader) => reader.poll_read(cx, buf),
+            None => Poll::Ready(Err(not_connected())),
+        }
+    }
+
+    /// reads exactly enough bytes to fill `buf`
+    pub async fn read_exact(&mut self, buf: &mut [u8]) -> IOResult<()> {
+        connected(self.reader.as_mut())?.read_exact(buf).await
+    }
+}
+
+impl OwnedWriteHalf {
+    /// puts the stream back together, failing if `read` belongs to a different stream
+    pub fn reunite(self, read: OwnedReadHalf) -> Result<TcpStream, ReuniteError> {
+        read.reunite(self)
+    }
+
+    /// writes as much of `buf` as the stream currently permits, see [`TcpStream::write`]
+    pub async fn write(&mut self, buf: &[u8]) -> IOResult<usize> {
+        connected(self.writer.as_mut())?.write(buf).await
+    }
+
+    /// poll form of [`OwnedWriteHalf::write`]
+    pub fn poll_write(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IOResult<usize>> {
+        match self.writer.as_mut() {
+            Some(writer) => writer.poll_write(cx, buf),
+            None => Poll::Ready(Err(not_connected())),
+        }
+    }
+
+    /// writes the whole of `buf`
+    pub async fn write_all(&mut self, buf: &[u8]) -> IOResult<()> {
+        connected(self.writer.as_mut())?.write_all(buf).await
+    }
+
+    /// waits until everything written so far has been handed over to the host
+    pub async fn flush(&mut self) -> IOResult<()> {
+        connected(self.writer.as_mut())?.flush().await
+    }
+
+    /// poll form of [`OwnedWriteHalf::flush`]
+    pub fn poll_flush(&mut self, cx: &mut Context<'_>) -> Poll<IOResult<()>> {
+        match self.writer.as_mut() {
+            Some(writer) => writer.poll_flush(cx),
+            None => Poll::Ready(Err(not_connected())),
+        }
+    }
+
+    /// flushes and then shuts down the sending side, the read half keeps receiving
+    pub fn poll_shutdown(&mut self, cx: &mut Context<'_>) -> Poll<IOResult<()>> {
+        match self.writer.as_mut() {
+            Some(writer) => writer.poll_shutdown(cx, &self.socket.tcp),
+            None => Poll::Ready(Err(not_connected())),
+        }
+    }
+}
+
+impl ReadHalf<'_> {
+    /// reads some bytes into `buf`, see [`TcpStream::read`]
+    pub async fn read(&mut self, buf: &mut [u8]) -> IOResult<usize> {
+        connected(self.reader.as_deref_mut())?.read(buf).await
+    }
+
+    /// poll form of [`ReadHalf::read`]
+    pub fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<IOResult<usize>> {
+        match self.reader.as_deref_mut() {
+            Some(reader) => reader.poll_read(cx, buf),
+            None => Poll::Ready(Err(not_connected())),
+        }
+    }
+
+    /// reads exactly enough bytes to fill `buf`
+    pub async fn read_exact(&mut self, buf: &mut [u8]) -> IOResult<()> {
+        connected(self.reader.as_deref_mut())?.read_exact(buf).await
+    }
+}
+
+impl WriteHalf<'_> {
+    /// writes as much of `buf` as the stream currently permits, see [`TcpStream::write`]
+    pub async fn write(&mut self, buf: &[u8]) -> IOResult<usize> {
+        connected(self.writer.as_deref_mut())?.write(buf).await
+    }
+
+    /// poll form of [`WriteHalf::write`]
+    pub fn poll_write(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IOResult<usize>> {
+        match self.writer.as_deref_mut() {
+            Some(writer) => writer.poll_write(cx, buf),
+            None => Poll::Ready(Err(not_connected())),
+        }
+    }
+
+    /// writes the whole of `buf`
+    pub async fn write_all(&mut self, buf: &[u8]) -> IOResult<()> {
+        connected(self.writer.as_deref_mut())?.write_all(buf).await
+    }
+
+    /// waits until everything written so far has been handed over to the host
+    pub async fn flush(&mut self) -> IOResult<()> {
+        connected(self.writer.as_deref_mut())?.flush().await
+    }
+
+    /// poll form of [`WriteHalf::flush`]
+    pub fn poll_flush(&mut self, cx: &mut Context<'_>) -> Poll<IOResult<()>> {
+        match self.writer.as_deref_mut() {
+            Some(writer) => writer.poll_flush(cx),
+            None => Poll::Ready(Err(not_connected())),
+        }
+    }
+
+    /// flushes and then shuts down the sending side, the read half keeps receiving
+    pub fn poll_shutdown(&mut self, cx: &mut Context<'_>) -> Poll<IOResult<()>> {
+        match self.writer.as_deref_mut() {
+            Some(writer) => writer.poll_shutdown(cx, &self.socket.tcp),
+            None => Poll::Ready(Err(not_connected())),
+        }
+    }
+}
+
+impl fmt::Debug for ReuniteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ReuniteError(..)")
+    }
+}
+
+impl fmt::Display for ReuniteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("tried to reunite halves that are not from the same stream")
+    }
+}
+
+impl Error for ReuniteError {}
@@ -0,0 +1,120 @@
+use super::{address_family, retry_would_block, wait_until_ready, IOResult, PollableRef};
+use crate::bindings::wasi::sockets::{
+    instance_network::instance_network,
+    network::{IpSocketAddress, Network},
+    udp::{self, IncomingDatagramStream, OutgoingDatagram, OutgoingDatagramStream},
+    udp_create_socket::create_udp_socket,
+};
+use crate::task;
+use std::{net::SocketAddr, sync::Arc};
+
+/// A UDP socket bound to a local address
+pub struct UdpSocket {
+    streams: Option<DatagramStreams>,
+    pollable: PollableRef,
+    socket: udp::UdpSocket,
+    network: Network,
+}
+
+struct DatagramStreams {
+    incoming_pollable: PollableRef,
+    outgoing_pollable: PollableRef,
+    incoming: IncomingDatagramStream,
+    outgoing: OutgoingDatagramStream,
+}
+
+impl UdpSocket {
+    /// binds a socket to the address, ready to send to and receive from any peer
+    pub async fn bind<A: Into<SocketAddr>>(address: A) -> IOResult<Self> {
+        let address = address.into();
+        let socket = create_udp_socket(address_family(&address))?;
+        let pollable = socket.subscribe();
+        let mut udp_socket = Self {
+            streams: None,
+            pollable: Arc::new(pollable),
+            socket,
+            network: instance_network(),
+        };
+        udp_socket
+            .socket
+            .start_bind(&udp_socket.network, address.into())?;
+        retry_would_block(&udp_socket.pollable, || udp_socket.socket.finish_bind()).await?;
+        udp_socket.open_streams(None)?;
+        Ok(udp_socket)
+    }
+
+    /// limits `send` and `recv` to a single peer. This only changes local configuration
+    pub fn connect<A: Into<SocketAddr>>(&mut self, address: A) -> IOResult<()> {
+        self.open_streams(Some(address.into().into()))
+    }
+
+    /// sends a datagram to the given peer, returning the number of bytes sent
+    pub async fn send_to<A: Into<SocketAddr>>(&self, buf: &[u8], address: A) -> IOResult<usize> {
+        self.send_datagram(buf, Some(address.into().into())).await
+    }
+
+    /// sends a datagram to the connected peer
+    pub async fn send(&self, buf: &[u8]) -> IOResult<usize> {
+        self.send_datagram(buf, None).await
+    }
+
+    /// receives a single datagram along with the address it came from.
+    /// Bytes that do not fit into `buf` are discarded
+    pub async fn recv_from(&self, buf: &mut [u8]) -> IOResult<(usize, SocketAddr)> {
+        let streams = self.streams()?;
+        loop {
+            //charged even when a datagram is waiting, so draining a busy socket still yields
+            task::consume_budget().await;
+            if let Some(datagram) = streams.incoming.receive(1)?.pop() {
+                let len = datagram.data.len().min(buf.len());
+                buf[..len].copy_from_slice(&datagram.data[..len]);
+                return Ok((len, datagram.remote_address.into()));
+            }
+            wait_until_ready(&streams.incoming_pollable).await;
+        }
+    }
+
+    /// receives a single datagram from the connected peer
+    pub async fn recv(&self, buf: &mut [u8]) -> IOResult<usize> {
+        self.recv_from(buf).await.map(|(len, _)| len)
+    }
+
+    async fn send_datagram(
+        &self,
+        buf: &[u8],
+        remote_address: Option<IpSocketAddress>,
+    ) -> IOResult<usize> {
+        let streams = self.streams()?;
+        let datagram = [OutgoingDatagram {
+            data: buf.to_vec(),
+            remote_address,
+        }];
+        loop {
+            task::consume_budget().await;
+            //every send has to be permitted by a preceding check_send
+            if streams.outgoing.check_send()? > 0 && streams.outgoing.send(&datagram)? > 0 {
+                return Ok(buf.len());
+            }
+            wait_until_ready(&streams.outgoing_pollable).await;
+        }
+    }
+
+    fn open_streams(&mut self, remote_address: Option<IpSocketAddress>) -> IOResult<()> {
+        //the host may trap if the previous pair is still alive
+        self.streams = None;
+        let (incoming, outgoing) = self.socket.stream(remote_address)?;
+        self.streams = Some(DatagramStreams {
+            incoming_pollable: Arc::new(incoming.subscribe()),
+            outgoing_pollable: Arc::new(outgoing.subscribe()),
+            incoming,
+            outgoing,
+        });
+        Ok(())
+    }
+
+    fn streams(&self) -> IOResult<&DatagramStreams> {
+        self.streams.as_ref().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotConnected, "socket is not bound")
+        })
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{
+    bindings::wasi::io::poll::Pollable,
+    engine::{Handle, Reactor},
+    poll_tasks::PollableKey,
+    task,
+};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Weak},
+    task::{ready, Context, Poll},
+};
+
+/// Resolves once the host reports its pollable as ready. Created by [`wait_for`]
+#[derive(Debug)]
+pub struct PollableFuture {
+    pollable: Arc<Pollable>,
+    key: Option<PollableKey>,
+    //set once registered so the pollable can be removed again on drop
+    reactor: Weak<Reactor<'static>>,
+}
+
+/// waits for any WASI pollable, such as the result of a `subscribe()` call on a stream,
+/// an incoming http response or a resolve-address-stream.
+///
+/// Pollables created by other bindings can be moved over with
+/// `Pollable::from_handle(pollable.take_handle())`
+pub fn wait_for(pollable: impl Into<Arc<Pollable>>) -> PollableFuture {
+    PollableFuture {
+        pollable: pollable.into(),
+        key: None,
+        reactor: Weak::new(),
+    }
+}
+
+impl PollableFuture {
+    pub fn pollable(&self) -> &Pollable {
+        &self.pollable
+    }
+}
+
+impl Future for PollableFuture {
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        ready!(task::poll_budget(cx));
+        let this = self.get_mut();
+        let handle = Handle::current();
+        let reactor = handle.reactor();
+        match this.key {
+            Some(key) if reactor.check_ready(key) => {
+                this.key = None;
+                Poll::Ready(())
+            }
+            Some(key) => {
+                //the future may have moved to another task since it registered
+                reactor.update_waker(key, cx.waker());
+                Poll::Pending
+            }
+            None => {
+                this.key = Some(reactor.register((this.pollable.clone(), cx.waker().clone())));
+                this.reactor = handle.downgrade();
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for PollableFuture {
+    fn drop(&mut self) {
+        if let (Some(reactor), Some(key)) = (self.reactor.upgrade(), self.key) {
+            reactor.deregister(key);
+        }
+    }
+}
@@ -1,17 +1,13 @@
-use crate::engine::REACTOR;
-use crate::poll_tasks::EventWithWaker;
-use dashmap::DashMap;
-use futures::FutureExt;
-use lazy_static::lazy_static;
-use std::future::Future;
-use std::task::Poll;
+use crate::engine::{Handle, Reactor};
+use crate::task;
+use crate::timer_queue::TimerId;
+use futures::{FutureExt, Stream};
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::sync::Weak;
+use std::task::{ready, Context, Poll};
 use std::time::Duration;
 use std::time::Instant;
-use uuid::Uuid;
-lazy_static! {
-    pub static ref TIMERS: DashMap<u32, EventWithWaker<Timer>> = DashMap::new();
-}
-
 #[derive(Debug, Clone)]
 pub struct Timer {
     at: Instant,
@@ -20,97 +16,276 @@ pub struct Timer {
 }
 
 impl Timer {
-    /// create a timer that resolves once it elapses
-    pub async fn sleep(until: std::time::Duration) {
-        let timeout = TimeFuture {
-            timer_key: format!("sleep-{}", Uuid::new_v4()),
-            timer: Self {
-                at: Instant::now(),
-                deadline: until,
-                elapsed: false,
-            },
-        };
-        timeout.await
+    /// create a timer that resolves once `duration` has passed
+    pub fn sleep(duration: Duration) -> Sleep {
+        Sleep::new(Self {
+            at: Instant::now(),
+            deadline: duration,
+            elapsed: false,
+        })
     }
 
-    pub async fn timeout<K, F: Future<Output = K>>(
-        fut: F,
-        deadline: std::time::Duration,
-    ) -> std::io::Result<K> {
-        let timer_future = TimeFuture {
-            timer_key: format!("timeout-{}", Uuid::new_v4()),
-            timer: Self {
-                at: Instant::now(),
-                deadline,
-                elapsed: false,
-            },
-        };
-        let timeout_future = TimeoutFuture { timer_future, fut };
-        timeout_future.await
+    /// create a timer that resolves once `deadline` is reached
+    pub fn sleep_until(deadline: Instant) -> Sleep {
+        Sleep::new(Self::at_instant(deadline))
+    }
+
+    /// run `fut`, failing with `TimedOut` if it does not finish within `deadline`
+    pub fn timeout<F: Future>(fut: F, deadline: Duration) -> Timeout<F> {
+        Timeout {
+            fut,
+            sleep: Self::sleep(deadline),
+        }
+    }
+
+    /// run `fut`, failing with `TimedOut` if it does not finish before `deadline`
+    pub fn timeout_at<F: Future>(fut: F, deadline: Instant) -> Timeout<F> {
+        Timeout {
+            fut,
+            sleep: Self::sleep_until(deadline),
+        }
+    }
+
+    //a timer expiring exactly at `deadline`, even when that is already in the past
+    fn at_instant(deadline: Instant) -> Self {
+        Self {
+            at: deadline,
+            deadline: Duration::ZERO,
+            elapsed: false,
+        }
+    }
+
+    /// create an interval whose first tick completes right away and then every `period`
+    ///
+    /// # Panics
+    /// Panics if `period` is zero
+    pub fn interval(period: Duration) -> Interval {
+        Self::interval_at(Instant::now(), period)
+    }
+
+    /// create an interval whose first tick completes at `start` and then every `period`
+    ///
+    /// # Panics
+    /// Panics if `period` is zero
+    pub fn interval_at(start: Instant, period: Duration) -> Interval {
+        assert!(!period.is_zero(), "interval period must be non-zero");
+        Interval {
+            timer_id: None,
+            period,
+            next: start,
+            missed_tick_behavior: MissedTickBehavior::default(),
+            reactor: Weak::new(),
+        }
     }
+
     pub fn update_elapsed(&mut self) {
         let new_now = Instant::now();
         let elapsed = new_now
             .checked_duration_since(self.at)
-            .map(|s| s > self.deadline)
+            .map(|s| s >= self.deadline)
             .unwrap_or_default();
         self.elapsed = elapsed;
     }
 
+    /// the instant at which this timer elapses
+    pub fn expires_at(&self) -> Instant {
+        self.at + self.deadline
+    }
+
     pub fn elapsed(&self) -> bool {
         self.elapsed
     }
 }
 
-struct TimeFuture {
-    timer_key: String,
+/// What an [`Interval`] does when ticks are missed because the task was busy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissedTickBehavior {
+    /// fire the missed ticks back to back until the interval has caught up
+    #[default]
+    Burst,
+    /// fire once and count the following ticks from now on
+    Delay,
+    /// fire once and continue with the next tick on the original schedule
+    Skip,
+}
+
+/// Ticks every period, created by [`Timer::interval`]. Also a `Stream` of the tick instants
+#[derive(Debug)]
+pub struct Interval {
+    //kept for the whole lifetime so every tick reuses the same reactor entry
+    timer_id: Option<TimerId>,
+    period: Duration,
+    next: Instant,
+    missed_tick_behavior: MissedTickBehavior,
+    reactor: Weak<Reactor<'static>>,
+}
+
+impl Interval {
+    /// waits for the next tick, returning the instant it was scheduled for
+    pub async fn tick(&mut self) -> Instant {
+        poll_fn(|cx| self.poll_tick(cx)).await
+    }
+
+    pub fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<Instant> {
+        ready!(task::poll_budget(cx));
+        let handle = Handle::current();
+        let reactor = handle.reactor();
+        let now = Instant::now();
+        if now < self.next {
+            self.timer_id =
+                Some(reactor.register_timer(self.timer_id, self.next, cx.waker().clone()));
+            self.reactor = handle.downgrade();
+            return Poll::Pending;
+        }
+        if let Some(id) = self.timer_id {
+            reactor.remove_timer(id);
+        }
+        let tick = self.next;
+        self.next = match self.missed_tick_behavior {
+            MissedTickBehavior::Burst => tick + self.period,
+            MissedTickBehavior::Delay => now + self.period,
+            MissedTickBehavior::Skip => {
+                let behind = (now - tick).as_nanos() % self.period.as_nanos();
+                now + self.period - Duration::from_nanos(behind as u64)
+            }
+        };
+        Poll::Ready(tick)
+    }
+
+    /// restarts the schedule so the next tick is one period from now
+    pub fn reset(&mut self) {
+        self.next = Instant::now() + self.period;
+    }
+
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    pub fn missed_tick_behavior(&self) -> MissedTickBehavior {
+        self.missed_tick_behavior
+    }
+
+    pub fn set_missed_tick_behavior(&mut self, behavior: MissedTickBehavior) {
+        self.missed_tick_behavior = behavior;
+    }
+}
+
+impl Stream for Interval {
+    type Item = Instant;
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_tick(cx).map(Some)
+    }
+}
+
+impl Drop for Interval {
+    fn drop(&mut self) {
+        if let (Some(reactor), Some(id)) = (self.reactor.upgrade(), self.timer_id) {
+            reactor.remove_timer(id);
+        }
+    }
+}
+
+/// Future returned by [`Timer::sleep`] and [`Timer::sleep_until`]. Can be stored and reset
+#[derive(Debug)]
+pub struct Sleep {
+    timer_id: Option<TimerId>,
     timer: Timer,
+    reactor: Weak<Reactor<'static>>,
 }
 
-impl Future for TimeFuture {
+impl Sleep {
+    fn new(timer: Timer) -> Self {
+        Self {
+            timer_id: None,
+            timer,
+            reactor: Weak::new(),
+        }
+    }
+
+    /// the instant at which this sleep resolves
+    pub fn deadline(&self) -> Instant {
+        self.timer.expires_at()
+    }
+
+    pub fn is_elapsed(&self) -> bool {
+        Instant::now() >= self.deadline()
+    }
+
+    /// moves the deadline, also after the sleep resolved. Useful for idle timeouts
+    pub fn reset(&mut self, deadline: Instant) {
+        self.timer = Timer::at_instant(deadline);
+        //an earlier deadline has to reach the reactor before it parks again
+        if let (Some(reactor), Some(id)) = (self.reactor.upgrade(), self.timer_id) {
+            reactor.reset_timer(id, self.deadline());
+        }
+    }
+}
+
+impl Future for Sleep {
     type Output = ();
-    fn poll(
-        self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
-    ) -> std::task::Poll<Self::Output> {
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        ready!(task::poll_budget(cx));
         let this = self.get_mut();
-        let reactor = &REACTOR;
-        if reactor.is_timer_pollable(&this.timer_key) {
-            let has_elapsed = reactor.timer_has_elapsed(&this.timer_key);
-            if has_elapsed {
-                reactor.remove_timer(&this.timer_key);
-                return std::task::Poll::Ready(());
+        let handle = Handle::current();
+        let reactor = handle.reactor();
+        this.timer.update_elapsed();
+        if this.timer.elapsed() {
+            if let Some(id) = this.timer_id {
+                reactor.remove_timer(id);
             }
-        } else {
-            reactor.register_timer(
-                this.timer_key.clone(),
-                (this.timer.clone(), cx.waker().clone()),
-            );
+            return Poll::Ready(());
         }
+        //(re)register so the reactor wakes the latest waker once the deadline passes
+        this.timer_id = Some(reactor.register_timer(
+            this.timer_id,
+            this.timer.expires_at(),
+            cx.waker().clone(),
+        ));
+        this.reactor = handle.downgrade();
 
-        std::task::Poll::Pending
+        Poll::Pending
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let (Some(reactor), Some(id)) = (self.reactor.upgrade(), self.timer_id) {
+            reactor.remove_timer(id);
+        }
     }
 }
 
 pin_project_lite::pin_project! {
-    pub struct TimeoutFuture<K,F:Future<Output = K>>
-    {
+    /// Future returned by [`Timer::timeout`] and [`Timer::timeout_at`]
+    #[derive(Debug)]
+    pub struct Timeout<F> {
         #[pin]
-        fut:F,
-        #[pin]
-        timer_future:TimeFuture
+        fut: F,
+        sleep: Sleep,
+    }
+}
+
+impl<F> Timeout<F> {
+    /// the instant at which the wrapped future times out
+    pub fn deadline(&self) -> Instant {
+        self.sleep.deadline()
+    }
+
+    pub fn get_ref(&self) -> &F {
+        &self.fut
+    }
+
+    pub fn into_inner(self) -> F {
+        self.fut
     }
 }
 
-impl<K, F: Future<Output = K>> Future for TimeoutFuture<K, F> {
-    type Output = Result<K, std::io::Error>;
-    fn poll(
-        self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
-    ) -> std::task::Poll<Self::Output> {
-        let mut this = self.project();
-        if this.timer_future.poll_unpin(cx).is_pending() {
-            match this.fut.poll_unpin(cx) {
+impl<F: Future> Future for Timeout<F> {
+    type Output = std::io::Result<F::Output>;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        if this.sleep.poll_unpin(cx).is_pending() {
+            match this.fut.poll(cx) {
                 Poll::Ready(ready) => Poll::Ready(Ok(ready)),
                 Poll::Pending => Poll::Pending,
             }
@@ -1,6 +1,15 @@
+#[allow(clippy::all)]
 pub mod bindings;
 pub mod engine;
 pub mod io;
+pub mod local;
 pub mod poll_tasks;
-pub use engine::WasmRuntimeAsyncEngine;
-pub use io::timer::Timer;
+pub mod slab;
+pub mod sync;
+pub mod task;
+pub mod task_store;
+pub mod timer_queue;
+pub use engine::{Handle, PanicPolicy, Runtime, WasmRuntimeAsyncEngine};
+pub use io::pollable::{wait_for, PollableFuture};
+pub use io::timer::{Interval, MissedTickBehavior, Sleep, Timeout, Timer};
+pub use local::{spawn_local, LocalSet};
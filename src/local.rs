@@ -0,0 +1,86 @@
+use crate::engine::{join_pair, JoinHandle, Runtime};
+use crate::task_store::TaskStore;
+use std::cell::RefCell;
+use std::future::Future;
+use std::rc::Rc;
+
+thread_local! {
+    //local set currently driven by block_on on this thread
+    static CURRENT_LOCAL: RefCell<Option<Rc<LocalTasks>>> = const { RefCell::new(None) };
+}
+
+/// A set of tasks that do not need to be `Send`. They only ever run on the thread driving the set,
+/// so they can hold `Rc`/`RefCell` state or socket types across an await
+#[derive(Default)]
+pub struct LocalSet {
+    tasks: Rc<LocalTasks>,
+}
+
+/// task store of a [`LocalSet`], polled by the runtime next to its own `Send` tasks.
+/// Ids are only unique within the set, so its woken tasks queue up here and not in the reactor
+pub(crate) type LocalTasks = TaskStore<dyn Future<Output = ()>>;
+
+//restores the previously driven local set once block_on returns
+struct LocalEnterGuard {
+    previous: Option<Rc<LocalTasks>>,
+}
+
+impl LocalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// spawns a task onto this set. It starts running once the set is driven by [`LocalSet::block_on`]
+    pub fn spawn_local<K: 'static, F: Future<Output = K> + 'static>(
+        &self,
+        future: F,
+    ) -> JoinHandle<K> {
+        push(&self.tasks, future, true)
+    }
+
+    /// runs `future` on `runtime`, driving the tasks of this set alongside it.
+    /// Returns as soon as `future` resolves, dropping the tasks still running
+    pub fn block_on<K: 'static, F: Future<Output = K> + 'static>(
+        &self,
+        runtime: &Runtime,
+        future: F,
+    ) -> K {
+        let join_handle = push(&self.tasks, future, false);
+        let _guard = self.enter();
+        runtime.run_local(join_handle, &self.tasks)
+    }
+
+    fn enter(&self) -> LocalEnterGuard {
+        let previous = CURRENT_LOCAL.with(|current| current.replace(Some(self.tasks.clone())));
+        LocalEnterGuard { previous }
+    }
+}
+
+/// spawns a task that does not need to be `Send` onto the [`LocalSet`] currently being driven.
+///
+/// # Panics
+/// Panics when called outside of [`LocalSet::block_on`] or [`Runtime::block_on_local`]
+pub fn spawn_local<K: 'static, F: Future<Output = K> + 'static>(future: F) -> JoinHandle<K> {
+    let tasks = CURRENT_LOCAL
+        .with(|current| current.borrow().clone())
+        .expect("spawn_local called outside of a LocalSet");
+    push(&tasks, future, true)
+}
+
+//polled for the first time once the set is driven
+fn push<K: 'static, F: Future<Output = K> + 'static>(
+    tasks: &LocalTasks,
+    future: F,
+    apply_policy: bool,
+) -> JoinHandle<K> {
+    tasks.push(|id, cancel_queue| {
+        let (task, join_handle) = join_pair(id, future, cancel_queue, apply_policy);
+        (Box::pin(task), join_handle)
+    })
+}
+
+impl Drop for LocalEnterGuard {
+    fn drop(&mut self) {
+        CURRENT_LOCAL.with(|current| *current.borrow_mut() = self.previous.take());
+    }
+}
@@ -1,55 +1,114 @@
-use std::{collections::HashMap, sync::Arc, task::Waker};
+use std::{sync::Arc, task::Waker, time::Duration};
 
 use crate::bindings::wasi::io::poll::{poll, Pollable};
+use crate::slab::{Key, Slab};
 
 pub type EventWithWaker<T> = (T, Waker);
 
+/// Identifies a pollable registered with the reactor
+pub type PollableKey = Key;
+
 ///Future that is used to poll changes from the host\
 #[derive(Default, Debug)]
 pub struct PollTasks {
-    pendings: HashMap<String, EventWithWaker<Arc<Pollable>>>,
-    finished: HashMap<String, Arc<Pollable>>,
+    pollables: Slab<PollEntry>,
+}
+
+#[derive(Debug)]
+struct PollEntry {
+    pollable: Arc<Pollable>,
+    waker: Waker,
+    //set once the host reports it, the entry stays until the owner checks it
+    ready: bool,
 }
 
 impl PollTasks {
-    pub(crate) fn push(&mut self, event_name: String, pollable: EventWithWaker<Arc<Pollable>>) {
-        self.pendings.insert(event_name, pollable);
+    pub(crate) fn push(&mut self, (pollable, waker): EventWithWaker<Arc<Pollable>>) -> PollableKey {
+        self.pollables.insert(PollEntry {
+            pollable,
+            waker,
+            ready: false,
+        })
+    }
+
+    pub(crate) fn update_waker(&mut self, key: PollableKey, waker: &Waker) {
+        if let Some(entry) = self.pollables.get_mut(key) {
+            if !entry.waker.will_wake(waker) {
+                entry.waker = waker.clone();
+            }
+        }
     }
 
-    pub(crate) fn contains(&self, key: &str) -> bool {
-        self.pendings.contains_key(key)
+    pub(crate) fn contains(&self, key: PollableKey) -> bool {
+        self.pollables.contains(key)
     }
 
-    pub(crate) fn check_if_ready(&mut self, key: &str) -> bool {
-        self.finished.remove(key).is_some()
+    pub(crate) fn remove(&mut self, key: PollableKey) {
+        self.pollables.remove(key);
+    }
+
+    //removes the entry once it became ready
+    pub(crate) fn check_if_ready(&mut self, key: PollableKey) -> bool {
+        let ready = self.pollables.get(key).is_some_and(|entry| entry.ready);
+        if ready {
+            self.pollables.remove(key);
+        }
+        ready
     }
 
     pub(crate) fn is_empty(&self) -> bool {
-        self.finished.is_empty() && self.pendings.is_empty()
+        self.pollables.is_empty()
     }
 
-    pub(crate) fn wait_for_pollables(&mut self) {
-        if self.pendings.is_empty() {
+    /// Blocks until one of the pending pollables is ready or `timeout` runs out.
+    /// A `None` timeout means there is no timer to wait on, so only I/O can wake us up.
+    pub(crate) fn wait_for_pollables(&mut self, timeout: Option<Duration>) {
+        let (keys, mut pending_polls): (Vec<_>, Vec<_>) = self
+            .pollables
+            .iter()
+            .filter(|(_, entry)| !entry.ready)
+            .map(|(key, entry)| (key, entry.pollable.as_ref()))
+            .unzip();
+        if keys.is_empty() {
+            if let Some(timeout) = timeout {
+                sleep(timeout);
+            }
             return;
         }
-        let pending_polls = self
-            .pendings
-            .values()
-            .map(|(pollable, _)| pollable.as_ref())
-            .collect::<Vec<_>>();
-        poll(pending_polls.as_slice());
-        let ready_set = self
-            .pendings
+        let clock = timeout.map(subscribe_timeout);
+        pending_polls.extend(clock.as_ref());
+        //the host answers with the positions of the ready pollables, the clock comes last
+        let ready_set = poll(pending_polls.as_slice());
+        for key in ready_set
             .iter()
-            .filter(|(_, (pollable, _))| pollable.ready())
-            .map(|(key, _)| key.clone())
-            .collect::<Vec<_>>();
-        //remove pollables
-        for key in ready_set.iter() {
-            if let Some((finished, waker)) = self.pendings.remove(key) {
-                waker.wake();
-                self.finished.insert(key.to_string(), finished);
+            .filter_map(|index| keys.get(*index as usize))
+        {
+            if let Some(entry) = self.pollables.get_mut(*key) {
+                entry.ready = true;
+                entry.waker.wake_by_ref();
             }
         }
     }
 }
+
+//creates a clock pollable that becomes ready once the timeout runs out
+fn subscribe_timeout(timeout: Duration) -> Pollable {
+    let nanos = u64::try_from(timeout.as_nanos()).unwrap_or(u64::MAX);
+    crate::bindings::wasi::clocks::monotonic_clock::subscribe_duration(nanos)
+}
+
+//parks the component until the earliest timer is due
+#[cfg(target_arch = "wasm32")]
+fn sleep(timeout: Duration) {
+    if !timeout.is_zero() {
+        poll(&[&subscribe_timeout(timeout)]);
+    }
+}
+
+//the host bindings are unavailable outside of wasm, so fall back to the thread
+#[cfg(not(target_arch = "wasm32"))]
+fn sleep(timeout: Duration) {
+    if !timeout.is_zero() {
+        std::thread::sleep(timeout);
+    }
+}
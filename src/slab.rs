@@ -0,0 +1,161 @@
+/// Index into a [`Slab`]. The generation tells a key apart from an older one whose slot was reused
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    index: u32,
+    generation: u32,
+}
+
+/// Storage handing out reusable generational indices, so lookups are a bounds check
+/// instead of a hash and stale keys are rejected instead of reaching a newer entry
+#[derive(Debug)]
+pub struct Slab<T> {
+    slots: Vec<Slot<T>>,
+    //indices of vacant slots, reused before the vector grows
+    free: Vec<u32>,
+    len: usize,
+}
+
+#[derive(Debug)]
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+impl<T> Default for Slab<T> {
+    fn default() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T> Slab<T> {
+    /// the key the next call to `insert` will return
+    pub(crate) fn vacant_key(&self) -> Key {
+        match self.free.last() {
+            Some(&index) => Key {
+                index,
+                generation: self.slots[index as usize].generation,
+            },
+            None => Key {
+                index: u32::try_from(self.slots.len()).expect("slab is full"),
+                generation: 0,
+            },
+        }
+    }
+
+    pub(crate) fn insert(&mut self, value: T) -> Key {
+        let key = self.vacant_key();
+        match self.free.pop() {
+            Some(index) => self.slots[index as usize].value = Some(value),
+            None => self.slots.push(Slot {
+                generation: 0,
+                value: Some(value),
+            }),
+        }
+        self.len += 1;
+        key
+    }
+
+    pub(crate) fn get(&self, key: Key) -> Option<&T> {
+        self.slots
+            .get(key.index as usize)
+            .filter(|slot| slot.generation == key.generation)
+            .and_then(|slot| slot.value.as_ref())
+    }
+
+    pub(crate) fn get_mut(&mut self, key: Key) -> Option<&mut T> {
+        self.slots
+            .get_mut(key.index as usize)
+            .filter(|slot| slot.generation == key.generation)
+            .and_then(|slot| slot.value.as_mut())
+    }
+
+    pub(crate) fn contains(&self, key: Key) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub(crate) fn remove(&mut self, key: Key) -> Option<T> {
+        let slot = self
+            .slots
+            .get_mut(key.index as usize)
+            .filter(|slot| slot.generation == key.generation)?;
+        let value = slot.value.take()?;
+        //outdates every copy of the key still around, like ids sitting in the ready queue
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(key.index);
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// removes every value, outdating their keys the same way `remove` does
+    pub(crate) fn drain(&mut self) -> Vec<T> {
+        let mut values = Vec::with_capacity(self.len);
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if let Some(value) = slot.value.take() {
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free.push(index as u32);
+                values.push(value);
+            }
+        }
+        self.len = 0;
+        values
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (Key, &T)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let key = Key {
+                index: index as u32,
+                generation: slot.generation,
+            };
+            slot.value.as_ref().map(|value| (key, value))
+        })
+    }
+
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Slab;
+
+    #[test]
+    fn test_stale_keys_are_rejected() {
+        let mut slab = Slab::default();
+        let first = slab.insert("first");
+        assert_eq!(slab.vacant_key(), slab.vacant_key());
+        assert_eq!(slab.remove(first), Some("first"));
+
+        //the slot is reused under a new generation
+        let second = slab.insert("second");
+        assert_ne!(first, second);
+        assert_eq!(slab.get(first), None);
+        assert_eq!(slab.remove(first), None);
+        assert_eq!(slab.get(second), Some(&"second"));
+        assert_eq!(slab.len(), 1);
+    }
+
+    #[test]
+    fn test_drain_outdates_keys() {
+        let mut slab = Slab::default();
+        let first = slab.insert("first");
+        slab.insert("second");
+        assert_eq!(slab.drain().len(), 2);
+        assert!(slab.is_empty());
+
+        //a value inserted after draining does not answer to the old key
+        let third = slab.insert("third");
+        assert_ne!(first, third);
+        assert_eq!(slab.get(first), None);
+        assert_eq!(slab.get(third), Some(&"third"));
+    }
+}
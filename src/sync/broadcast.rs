@@ -0,0 +1,252 @@
+use crate::task;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::future::poll_fn;
+use std::sync::{Arc, Mutex};
+use std::task::{ready, Context, Poll, Waker};
+
+/// Creates a channel where every receiver sees every message. The channel keeps the last
+/// `capacity` messages; a receiver falling further behind gets [`RecvError::Lagged`]
+///
+/// # Panics
+/// Panics if `capacity` is zero
+pub fn channel<T: Clone>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "broadcast channel requires capacity > 0");
+    let shared = Arc::new(Mutex::new(State {
+        buffer: VecDeque::with_capacity(capacity),
+        head: 0,
+        capacity,
+        senders: 1,
+        receivers: 0,
+        next_receiver_id: 0,
+        wakers: HashMap::new(),
+    }));
+    let receiver = Receiver::new(&shared);
+    (Sender { shared }, receiver)
+}
+
+pub struct Sender<T> {
+    shared: Arc<Mutex<State<T>>>,
+}
+
+pub struct Receiver<T> {
+    shared: Arc<Mutex<State<T>>>,
+    id: u64,
+    //position of the next message this receiver reads
+    next: u64,
+}
+
+struct State<T> {
+    buffer: VecDeque<T>,
+    //position of buffer[0]
+    head: u64,
+    capacity: usize,
+    senders: usize,
+    receivers: usize,
+    next_receiver_id: u64,
+    //one entry per waiting receiver
+    wakers: HashMap<u64, Waker>,
+}
+
+/// Every receiver is gone, the message is handed back
+pub struct SendError<T>(pub T);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvError {
+    /// every sender is gone and all messages were received
+    Closed,
+    /// the receiver fell behind and this many messages were dropped before it could read them.
+    /// The next receive continues with the oldest message still held
+    Lagged(u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// no new message right now
+    Empty,
+    /// every sender is gone and all messages were received
+    Closed,
+    /// see [`RecvError::Lagged`]
+    Lagged(u64),
+}
+
+impl<T> State<T> {
+    fn tail(&self) -> u64 {
+        self.head + self.buffer.len() as u64
+    }
+
+    fn take_wakers(&mut self) -> Vec<Waker> {
+        self.wakers.drain().map(|(_, waker)| waker).collect()
+    }
+}
+
+impl<T: Clone> Sender<T> {
+    /// sends `value` to every receiver, returning how many receivers there are
+    pub fn send(&self, value: T) -> Result<usize, SendError<T>> {
+        let (receivers, wakers) = {
+            let mut state = self.shared.lock().unwrap();
+            if state.receivers == 0 {
+                return Err(SendError(value));
+            }
+            state.buffer.push_back(value);
+            if state.buffer.len() > state.capacity {
+                state.buffer.pop_front();
+                state.head += 1;
+            }
+            (state.receivers, state.take_wakers())
+        };
+        wakers.into_iter().for_each(Waker::wake);
+        Ok(receivers)
+    }
+
+    /// creates a receiver that sees every message sent from now on
+    pub fn subscribe(&self) -> Receiver<T> {
+        Receiver::new(&self.shared)
+    }
+
+    pub fn receiver_count(&self) -> usize {
+        self.shared.lock().unwrap().receivers
+    }
+}
+
+impl<T: Clone> Receiver<T> {
+    fn new(shared: &Arc<Mutex<State<T>>>) -> Self {
+        let mut state = shared.lock().unwrap();
+        state.receivers += 1;
+        state.next_receiver_id += 1;
+        Self {
+            shared: shared.clone(),
+            id: state.next_receiver_id,
+            next: state.tail(),
+        }
+    }
+
+    /// waits for the next message
+    pub async fn recv(&mut self) -> Result<T, RecvError> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let state = self.shared.lock().unwrap();
+        match next_message(&state, &mut self.next) {
+            Some(Ok(value)) => Ok(value),
+            Some(Err(RecvError::Lagged(missed))) => Err(TryRecvError::Lagged(missed)),
+            Some(Err(RecvError::Closed)) => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// creates a receiver that sees every message sent from now on
+    pub fn resubscribe(&self) -> Self {
+        Self::new(&self.shared)
+    }
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, RecvError>> {
+        ready!(task::poll_budget(cx));
+        let mut state = self.shared.lock().unwrap();
+        match next_message(&state, &mut self.next) {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.wakers.insert(self.id, cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+//reads the message at `next`, None when nothing new is available yet
+fn next_message<T: Clone>(state: &State<T>, next: &mut u64) -> Option<Result<T, RecvError>> {
+    if *next < state.head {
+        let missed = state.head - *next;
+        *next = state.head;
+        return Some(Err(RecvError::Lagged(missed)));
+    }
+    if *next < state.tail() {
+        let value = state.buffer[(*next - state.head) as usize].clone();
+        *next += 1;
+        return Some(Ok(value));
+    }
+    (state.senders == 0).then_some(Err(RecvError::Closed))
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.lock().unwrap().senders += 1;
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let wakers = {
+            let mut state = self.shared.lock().unwrap();
+            state.senders -= 1;
+            if state.senders > 0 {
+                return;
+            }
+            state.take_wakers()
+        };
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock().unwrap();
+        state.receivers -= 1;
+        state.wakers.remove(&self.id);
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendError").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "channel closed")
+    }
+}
+
+impl<T> std::error::Error for SendError<T> {}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecvError::Closed => write!(f, "channel closed"),
+            RecvError::Lagged(missed) => write!(f, "receiver lagged behind by {missed} messages"),
+        }
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => write!(f, "channel empty"),
+            TryRecvError::Closed => write!(f, "channel closed"),
+            TryRecvError::Lagged(missed) => {
+                write!(f, "receiver lagged behind by {missed} messages")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
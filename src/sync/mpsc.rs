@@ -0,0 +1,326 @@
+use super::semaphore::Semaphore;
+use crate::task;
+use futures::Stream;
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::poll_fn;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{ready, Context, Poll, Waker};
+
+//handed to waiting senders once the receiver is gone so they can observe the closed channel
+const CLOSED_PERMITS: usize = usize::MAX >> 3;
+
+/// Creates a channel holding at most `buffer` messages. Senders wait for room once it is full
+///
+/// # Panics
+/// Panics if `buffer` is zero
+pub fn channel<T>(buffer: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(buffer > 0, "mpsc bounded channel requires buffer > 0");
+    let chan = Chan::new(Some(Semaphore::new(buffer)));
+    (Sender { chan: chan.clone() }, Receiver { chan })
+}
+
+/// Creates a channel without a limit on the number of queued messages
+pub fn unbounded_channel<T>() -> (UnboundedSender<T>, Receiver<T>) {
+    let chan = Chan::new(None);
+    (UnboundedSender { chan: chan.clone() }, Receiver { chan })
+}
+
+/// Sending half of a bounded channel
+pub struct Sender<T> {
+    chan: Arc<Chan<T>>,
+}
+
+/// Sending half of an unbounded channel
+pub struct UnboundedSender<T> {
+    chan: Arc<Chan<T>>,
+}
+
+/// Receiving half of a channel. Yields `None` once every sender is dropped and the queue is empty
+pub struct Receiver<T> {
+    chan: Arc<Chan<T>>,
+}
+
+struct Chan<T> {
+    state: Mutex<State<T>>,
+    //bounded channels hold one permit per queued message
+    semaphore: Option<Semaphore>,
+}
+
+struct State<T> {
+    queue: VecDeque<T>,
+    senders: usize,
+    closed: bool,
+    rx_waker: Option<Waker>,
+}
+
+/// The receiver is gone, the message is handed back
+pub struct SendError<T>(pub T);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TrySendError<T> {
+    /// the channel is at capacity
+    Full(T),
+    /// the receiver is gone
+    Closed(T),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// no message is queued right now
+    Empty,
+    /// no message is queued and every sender is gone
+    Disconnected,
+}
+
+impl<T> Chan<T> {
+    fn new(semaphore: Option<Semaphore>) -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(State {
+                queue: VecDeque::new(),
+                senders: 1,
+                closed: false,
+                rx_waker: None,
+            }),
+            semaphore,
+        })
+    }
+
+    fn push(&self, value: T) -> Result<(), SendError<T>> {
+        let waker = {
+            let mut state = self.state.lock().unwrap();
+            if state.closed {
+                return Err(SendError(value));
+            }
+            state.queue.push_back(value);
+            state.rx_waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    fn is_closed(&self) -> bool {
+        self.state.lock().unwrap().closed
+    }
+
+    fn poll_recv(&self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        ready!(task::poll_budget(cx));
+        let mut state = self.state.lock().unwrap();
+        if let Some(value) = state.queue.pop_front() {
+            drop(state);
+            self.free_slot();
+            return Poll::Ready(Some(value));
+        }
+        if state.senders == 0 || state.closed {
+            return Poll::Ready(None);
+        }
+        state.rx_waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+
+    fn try_recv(&self) -> Result<T, TryRecvError> {
+        let mut state = self.state.lock().unwrap();
+        match state.queue.pop_front() {
+            Some(value) => {
+                drop(state);
+                self.free_slot();
+                Ok(value)
+            }
+            None if state.senders == 0 || state.closed => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    fn free_slot(&self) {
+        if let Some(semaphore) = &self.semaphore {
+            semaphore.release(1);
+        }
+    }
+
+    fn close(&self) {
+        let newly_closed = !std::mem::replace(&mut self.state.lock().unwrap().closed, true);
+        if let (true, Some(semaphore)) = (newly_closed, &self.semaphore) {
+            semaphore.add_permits(CLOSED_PERMITS);
+        }
+    }
+
+    fn add_sender(&self) {
+        self.state.lock().unwrap().senders += 1;
+    }
+
+    fn drop_sender(&self) {
+        let waker = {
+            let mut state = self.state.lock().unwrap();
+            state.senders -= 1;
+            if state.senders > 0 {
+                return;
+            }
+            state.rx_waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Sender<T> {
+    /// waits for room in the channel and queues `value`
+    pub async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        if let Some(semaphore) = &self.chan.semaphore {
+            semaphore.acquire_inner(1).await;
+        }
+        self.chan.push(value)
+    }
+
+    /// queues `value` if there is room right now
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        if self.chan.is_closed() {
+            return Err(TrySendError::Closed(value));
+        }
+        let semaphore = self.chan.semaphore.as_ref().expect("bounded channel");
+        if !semaphore.try_acquire_inner(1) {
+            return Err(TrySendError::Full(value));
+        }
+        self.chan
+            .push(value)
+            .map_err(|SendError(value)| TrySendError::Closed(value))
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.chan.is_closed()
+    }
+}
+
+impl<T> UnboundedSender<T> {
+    /// queues `value` without waiting
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        self.chan.push(value)
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.chan.is_closed()
+    }
+}
+
+impl<T> Receiver<T> {
+    /// waits for the next message. Returns `None` once the channel is closed and drained
+    pub async fn recv(&mut self) -> Option<T> {
+        poll_fn(|cx| self.chan.poll_recv(cx)).await
+    }
+
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        self.chan.try_recv()
+    }
+
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.chan.poll_recv(cx)
+    }
+
+    /// stops accepting messages. Messages already queued can still be received
+    pub fn close(&mut self) {
+        self.chan.close();
+    }
+}
+
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.chan.poll_recv(cx)
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.chan.add_sender();
+        Self {
+            chan: self.chan.clone(),
+        }
+    }
+}
+
+impl<T> Clone for UnboundedSender<T> {
+    fn clone(&self) -> Self {
+        self.chan.add_sender();
+        Self {
+            chan: self.chan.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.chan.drop_sender();
+    }
+}
+
+impl<T> Drop for UnboundedSender<T> {
+    fn drop(&mut self) {
+        self.chan.drop_sender();
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.chan.close();
+        //nobody can receive them anymore
+        let queued = std::mem::take(&mut self.chan.state.lock().unwrap().queue);
+        drop(queued);
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Debug for UnboundedSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnboundedSender").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendError").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "channel closed")
+    }
+}
+
+impl<T> std::error::Error for SendError<T> {}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrySendError::Full(_) => write!(f, "channel full"),
+            TrySendError::Closed(_) => write!(f, "channel closed"),
+        }
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for TrySendError<T> {}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => write!(f, "channel empty"),
+            TryRecvError::Disconnected => write!(f, "channel disconnected"),
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
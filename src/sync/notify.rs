@@ -0,0 +1,138 @@
+use crate::task;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{ready, Context, Poll, Waker};
+
+const WAITING: u8 = 0;
+const NOTIFIED_ONE: u8 = 1;
+const NOTIFIED_ALL: u8 = 2;
+
+/// Wakes tasks waiting on [`Notify::notified`]. Waiters are notified in the order they started waiting
+#[derive(Debug, Default)]
+pub struct Notify {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    //set by notify_one when nobody is waiting, consumed by the next waiter
+    permit: bool,
+    waiters: VecDeque<Arc<Waiter>>,
+}
+
+#[derive(Debug)]
+struct Waiter {
+    //only changed while the state lock is held
+    notified: AtomicU8,
+    waker: Mutex<Option<Waker>>,
+}
+
+/// Future returned by [`Notify::notified`]
+#[derive(Debug)]
+pub struct Notified<'a> {
+    notify: &'a Notify,
+    waiter: Option<Arc<Waiter>>,
+}
+
+impl Notify {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// waits for a notification. Starts waiting on the first poll
+    pub fn notified(&self) -> Notified<'_> {
+        Notified {
+            notify: self,
+            waiter: None,
+        }
+    }
+
+    /// wakes the longest waiting task. If nobody is waiting, the notification is stored
+    /// and the next call to `notified` completes right away
+    pub fn notify_one(&self) {
+        let waker = {
+            let mut state = self.state.lock().unwrap();
+            match state.waiters.pop_front() {
+                Some(waiter) => {
+                    waiter.notified.store(NOTIFIED_ONE, Ordering::Release);
+                    waiter.waker.lock().unwrap().take()
+                }
+                None => {
+                    state.permit = true;
+                    None
+                }
+            }
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    /// wakes every task currently waiting. Nothing is stored for later waiters
+    pub fn notify_waiters(&self) {
+        let wakers: Vec<_> = {
+            let mut state = self.state.lock().unwrap();
+            state
+                .waiters
+                .drain(..)
+                .filter_map(|waiter| {
+                    waiter.notified.store(NOTIFIED_ALL, Ordering::Release);
+                    waiter.waker.lock().unwrap().take()
+                })
+                .collect()
+        };
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+impl Future for Notified<'_> {
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        ready!(task::poll_budget(cx));
+        let this = self.get_mut();
+        let mut state = this.notify.state.lock().unwrap();
+        match &this.waiter {
+            Some(waiter) if waiter.notified.load(Ordering::Acquire) != WAITING => {
+                this.waiter = None;
+                Poll::Ready(())
+            }
+            Some(waiter) => {
+                *waiter.waker.lock().unwrap() = Some(cx.waker().clone());
+                Poll::Pending
+            }
+            None if state.permit => {
+                state.permit = false;
+                Poll::Ready(())
+            }
+            None => {
+                let waiter = Arc::new(Waiter {
+                    notified: AtomicU8::new(WAITING),
+                    waker: Mutex::new(Some(cx.waker().clone())),
+                });
+                state.waiters.push_back(waiter.clone());
+                this.waiter = Some(waiter);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for Notified<'_> {
+    fn drop(&mut self) {
+        let Some(waiter) = self.waiter.take() else {
+            return;
+        };
+        let notified = {
+            let mut state = self.notify.state.lock().unwrap();
+            state.waiters.retain(|queued| !Arc::ptr_eq(queued, &waiter));
+            waiter.notified.load(Ordering::Acquire)
+        };
+        //a notify_one we never observed is passed on instead of being lost
+        if notified == NOTIFIED_ONE {
+            self.notify.notify_one();
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use std::fmt;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// Creates a channel carrying a single value. The [`Receiver`] is a future resolving to it
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let inner = Arc::new(Mutex::new(State {
+        value: None,
+        tx_dropped: false,
+        rx_dropped: false,
+        rx_waker: None,
+        tx_waker: None,
+    }));
+    (
+        Sender {
+            inner: inner.clone(),
+        },
+        Receiver { inner },
+    )
+}
+
+pub struct Sender<T> {
+    inner: Arc<Mutex<State<T>>>,
+}
+
+pub struct Receiver<T> {
+    inner: Arc<Mutex<State<T>>>,
+}
+
+struct State<T> {
+    value: Option<T>,
+    tx_dropped: bool,
+    rx_dropped: bool,
+    rx_waker: Option<Waker>,
+    //woken when the receiver goes away, see Sender::closed
+    tx_waker: Option<Waker>,
+}
+
+/// The sender was dropped without sending a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecvError(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// no value was sent yet
+    Empty,
+    /// the sender was dropped without sending a value
+    Closed,
+}
+
+impl<T> Sender<T> {
+    /// sends `value`, handing it back if the receiver is gone
+    pub fn send(self, value: T) -> Result<(), T> {
+        let waker = {
+            let mut state = self.inner.lock().unwrap();
+            if state.rx_dropped {
+                return Err(value);
+            }
+            state.value = Some(value);
+            state.rx_waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.inner.lock().unwrap().rx_dropped
+    }
+
+    /// waits until the receiver is dropped or closed, so the value no longer needs computing
+    pub async fn closed(&mut self) {
+        poll_fn(|cx| {
+            let mut state = self.inner.lock().unwrap();
+            if state.rx_dropped {
+                return Poll::Ready(());
+            }
+            state.tx_waker = Some(cx.waker().clone());
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+impl<T> Receiver<T> {
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let mut state = self.inner.lock().unwrap();
+        match state.value.take() {
+            Some(value) => Ok(value),
+            None if state.tx_dropped => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// refuses the value. The sender sees the channel as closed
+    pub fn close(&mut self) {
+        let waker = {
+            let mut state = self.inner.lock().unwrap();
+            state.rx_dropped = true;
+            state.tx_waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Future for Receiver<T> {
+    type Output = Result<T, RecvError>;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.inner.lock().unwrap();
+        if let Some(value) = state.value.take() {
+            return Poll::Ready(Ok(value));
+        }
+        if state.tx_dropped {
+            return Poll::Ready(Err(RecvError(())));
+        }
+        state.rx_waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let waker = {
+            let mut state = self.inner.lock().unwrap();
+            state.tx_dropped = true;
+            state.rx_waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sender dropped without sending a value")
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => write!(f, "no value sent yet"),
+            TryRecvError::Closed => write!(f, "sender dropped without sending a value"),
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
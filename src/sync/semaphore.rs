@@ -0,0 +1,228 @@
+use crate::task;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{ready, Context, Poll, Waker};
+
+/// An async counting semaphore. Waiters are served strictly in the order they started waiting,
+/// so a large request at the front of the queue is not starved by smaller ones behind it
+#[derive(Debug)]
+pub struct Semaphore {
+    state: Mutex<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    permits: usize,
+    waiters: VecDeque<Arc<Waiter>>,
+}
+
+#[derive(Debug)]
+struct Waiter {
+    needed: usize,
+    //only changed while the state lock is held
+    granted: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+/// Permits borrowed from a [`Semaphore`], given back when dropped
+#[derive(Debug)]
+pub struct SemaphorePermit<'a> {
+    semaphore: &'a Semaphore,
+    permits: usize,
+}
+
+/// Permits taken from an `Arc<Semaphore>`, given back when dropped.
+/// Unlike [`SemaphorePermit`] it can be moved into a spawned task
+#[derive(Debug)]
+pub struct OwnedSemaphorePermit {
+    semaphore: Arc<Semaphore>,
+    permits: usize,
+}
+
+//resolves once `needed` permits have been handed over, queueing behind earlier waiters
+pub(crate) struct Acquire<'a> {
+    semaphore: &'a Semaphore,
+    needed: usize,
+    waiter: Option<Arc<Waiter>>,
+}
+
+impl Semaphore {
+    pub fn new(permits: usize) -> Self {
+        Self {
+            state: Mutex::new(State {
+                permits,
+                waiters: VecDeque::new(),
+            }),
+        }
+    }
+
+    pub fn available_permits(&self) -> usize {
+        self.state.lock().unwrap().permits
+    }
+
+    /// adds `n` permits, waking queued waiters that can now be served
+    pub fn add_permits(&self, n: usize) {
+        self.release(n);
+    }
+
+    pub async fn acquire(&self) -> SemaphorePermit<'_> {
+        self.acquire_many(1).await
+    }
+
+    pub async fn acquire_many(&self, n: usize) -> SemaphorePermit<'_> {
+        self.acquire_inner(n).await;
+        SemaphorePermit {
+            semaphore: self,
+            permits: n,
+        }
+    }
+
+    pub async fn acquire_owned(self: Arc<Self>) -> OwnedSemaphorePermit {
+        self.acquire_many_owned(1).await
+    }
+
+    pub async fn acquire_many_owned(self: Arc<Self>, n: usize) -> OwnedSemaphorePermit {
+        self.acquire_inner(n).await;
+        OwnedSemaphorePermit {
+            semaphore: self,
+            permits: n,
+        }
+    }
+
+    /// takes a permit without waiting. Fails if none is free or other tasks are already queued
+    pub fn try_acquire(&self) -> Option<SemaphorePermit<'_>> {
+        self.try_acquire_many(1)
+    }
+
+    pub fn try_acquire_many(&self, n: usize) -> Option<SemaphorePermit<'_>> {
+        self.try_acquire_inner(n).then_some(SemaphorePermit {
+            semaphore: self,
+            permits: n,
+        })
+    }
+
+    pub fn try_acquire_owned(self: Arc<Self>) -> Option<OwnedSemaphorePermit> {
+        self.try_acquire_inner(1).then_some(OwnedSemaphorePermit {
+            semaphore: self,
+            permits: 1,
+        })
+    }
+
+    pub(crate) fn acquire_inner(&self, needed: usize) -> Acquire<'_> {
+        Acquire {
+            semaphore: self,
+            needed,
+            waiter: None,
+        }
+    }
+
+    pub(crate) fn try_acquire_inner(&self, needed: usize) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.waiters.is_empty() && state.permits >= needed {
+            state.permits -= needed;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(crate) fn release(&self, n: usize) {
+        let mut wakers = Vec::new();
+        {
+            let mut state = self.state.lock().unwrap();
+            let State { permits, waiters } = &mut *state;
+            *permits += n;
+            while let Some(waiter) = waiters.front() {
+                if waiter.needed > *permits {
+                    break;
+                }
+                *permits -= waiter.needed;
+                waiter.granted.store(true, Ordering::Release);
+                if let Some(waker) = waiter.waker.lock().unwrap().take() {
+                    wakers.push(waker);
+                }
+                waiters.pop_front();
+            }
+        }
+        //woken outside of the lock so the woken tasks never contend with us
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+impl Future for Acquire<'_> {
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        ready!(task::poll_budget(cx));
+        let this = self.get_mut();
+        let mut state = this.semaphore.state.lock().unwrap();
+        match &this.waiter {
+            Some(waiter) if waiter.granted.load(Ordering::Acquire) => {
+                this.waiter = None;
+                Poll::Ready(())
+            }
+            Some(waiter) => {
+                *waiter.waker.lock().unwrap() = Some(cx.waker().clone());
+                Poll::Pending
+            }
+            None if state.waiters.is_empty() && state.permits >= this.needed => {
+                state.permits -= this.needed;
+                Poll::Ready(())
+            }
+            None => {
+                let waiter = Arc::new(Waiter {
+                    needed: this.needed,
+                    granted: AtomicBool::new(false),
+                    waker: Mutex::new(Some(cx.waker().clone())),
+                });
+                state.waiters.push_back(waiter.clone());
+                this.waiter = Some(waiter);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for Acquire<'_> {
+    fn drop(&mut self) {
+        let Some(waiter) = self.waiter.take() else {
+            return;
+        };
+        let granted = {
+            let mut state = self.semaphore.state.lock().unwrap();
+            state.waiters.retain(|queued| !Arc::ptr_eq(queued, &waiter));
+            waiter.granted.load(Ordering::Acquire)
+        };
+        //hand back permits granted after the last poll, and let the waiters behind us move up
+        self.semaphore
+            .release(if granted { waiter.needed } else { 0 });
+    }
+}
+
+impl SemaphorePermit<'_> {
+    /// keeps the permits out of the semaphore for good
+    pub fn forget(mut self) {
+        self.permits = 0;
+    }
+}
+
+impl OwnedSemaphorePermit {
+    /// keeps the permits out of the semaphore for good
+    pub fn forget(mut self) {
+        self.permits = 0;
+    }
+}
+
+impl Drop for SemaphorePermit<'_> {
+    fn drop(&mut self) {
+        self.semaphore.release(self.permits);
+    }
+}
+
+impl Drop for OwnedSemaphorePermit {
+    fn drop(&mut self) {
+        self.semaphore.release(self.permits);
+    }
+}
@@ -0,0 +1,254 @@
+use crate::task;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::poll_fn;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard};
+use std::task::{ready, Poll, Waker};
+
+/// Creates a channel holding a single value. Receivers see the latest value and can wait for it to change
+pub fn channel<T>(init: T) -> (Sender<T>, Receiver<T>) {
+    let shared = Arc::new(Shared {
+        value: RwLock::new(init),
+        state: Mutex::new(State {
+            version: 0,
+            sender_dropped: false,
+            receivers: 1,
+            next_receiver_id: 1,
+            wakers: HashMap::new(),
+        }),
+    });
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver {
+            shared,
+            id: 1,
+            seen: 0,
+        },
+    )
+}
+
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+    id: u64,
+    //version of the value this receiver last marked as seen
+    seen: u64,
+}
+
+/// Borrow of the current value. Holding it blocks the sender, so keep it short and never across an await
+pub struct Ref<'a, T> {
+    guard: RwLockReadGuard<'a, T>,
+}
+
+struct Shared<T> {
+    value: RwLock<T>,
+    state: Mutex<State>,
+}
+
+struct State {
+    version: u64,
+    sender_dropped: bool,
+    receivers: usize,
+    next_receiver_id: u64,
+    //one entry per receiver waiting in changed()
+    wakers: HashMap<u64, Waker>,
+}
+
+/// Every receiver is gone, the value is handed back
+pub struct SendError<T>(pub T);
+
+/// The sender was dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecvError(());
+
+impl<T> Shared<T> {
+    fn bump_version(&self) {
+        let wakers: Vec<_> = {
+            let mut state = self.state.lock().unwrap();
+            state.version += 1;
+            state.wakers.drain().map(|(_, waker)| waker).collect()
+        };
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+impl<T> Sender<T> {
+    /// replaces the value and notifies every receiver. Fails if there are no receivers left
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        if self.is_closed() {
+            return Err(SendError(value));
+        }
+        self.send_replace(value);
+        Ok(())
+    }
+
+    /// replaces the value even without receivers, returning the previous one
+    pub fn send_replace(&self, value: T) -> T {
+        let previous = std::mem::replace(&mut *self.shared.value.write().unwrap(), value);
+        self.shared.bump_version();
+        previous
+    }
+
+    /// modifies the value in place and notifies every receiver
+    pub fn send_modify(&self, modify: impl FnOnce(&mut T)) {
+        modify(&mut self.shared.value.write().unwrap());
+        self.shared.bump_version();
+    }
+
+    pub fn borrow(&self) -> Ref<'_, T> {
+        Ref {
+            guard: self.shared.value.read().unwrap(),
+        }
+    }
+
+    /// creates a receiver that treats the current value as seen
+    pub fn subscribe(&self) -> Receiver<T> {
+        Receiver::new(&self.shared)
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.receiver_count() == 0
+    }
+
+    pub fn receiver_count(&self) -> usize {
+        self.shared.state.lock().unwrap().receivers
+    }
+}
+
+impl<T> Receiver<T> {
+    fn new(shared: &Arc<Shared<T>>) -> Self {
+        let mut state = shared.state.lock().unwrap();
+        state.receivers += 1;
+        state.next_receiver_id += 1;
+        Self {
+            shared: shared.clone(),
+            id: state.next_receiver_id,
+            seen: state.version,
+        }
+    }
+
+    /// borrows the current value without marking it as seen
+    pub fn borrow(&self) -> Ref<'_, T> {
+        Ref {
+            guard: self.shared.value.read().unwrap(),
+        }
+    }
+
+    /// borrows the current value and marks it as seen
+    pub fn borrow_and_update(&mut self) -> Ref<'_, T> {
+        let guard = self.shared.value.read().unwrap();
+        self.seen = self.shared.state.lock().unwrap().version;
+        Ref { guard }
+    }
+
+    /// whether a value was sent since the last one marked as seen
+    pub fn has_changed(&self) -> Result<bool, RecvError> {
+        let state = self.shared.state.lock().unwrap();
+        if state.version != self.seen {
+            Ok(true)
+        } else if state.sender_dropped {
+            Err(RecvError(()))
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// waits until a value newer than the last seen one is sent and marks it as seen.
+    /// Fails once the sender is dropped
+    pub async fn changed(&mut self) -> Result<(), RecvError> {
+        poll_fn(|cx| {
+            ready!(task::poll_budget(cx));
+            let mut state = self.shared.state.lock().unwrap();
+            if state.version != self.seen {
+                self.seen = state.version;
+                return Poll::Ready(Ok(()));
+            }
+            if state.sender_dropped {
+                return Poll::Ready(Err(RecvError(())));
+            }
+            state.wakers.insert(self.id, cx.waker().clone());
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+impl<T> Deref for Ref<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        let mut receiver = Self::new(&self.shared);
+        receiver.seen = self.seen;
+        receiver
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let wakers: Vec<_> = {
+            let mut state = self.shared.state.lock().unwrap();
+            state.sender_dropped = true;
+            state.wakers.drain().map(|(_, waker)| waker).collect()
+        };
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.receivers -= 1;
+        state.wakers.remove(&self.id);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Ref<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendError").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "channel closed")
+    }
+}
+
+impl<T> std::error::Error for SendError<T> {}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sender dropped")
+    }
+}
+
+impl std::error::Error for RecvError {}
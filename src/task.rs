@@ -0,0 +1,74 @@
+use std::cell::Cell;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+//polls of runtime leaf futures a task may make before it has to give the others a turn
+const BUDGET: u8 = 128;
+
+thread_local! {
+    //budget left to the task being polled, None outside of the runtime
+    static BUDGET_LEFT: Cell<Option<u8>> = const { Cell::new(None) };
+}
+
+//restores the budget of the enclosing poll, if any, once the task returns
+struct BudgetGuard {
+    previous: Option<u8>,
+}
+
+//resolves on its second poll, after the task went through the back of the ready queue once
+struct YieldNow {
+    yielded: bool,
+}
+
+/// yields to the runtime, letting every other ready task run before this one continues
+pub async fn yield_now() {
+    YieldNow { yielded: false }.await
+}
+
+//polls a task with a fresh budget
+pub(crate) fn with_budget<R>(poll: impl FnOnce() -> R) -> R {
+    let _guard = BudgetGuard {
+        previous: BUDGET_LEFT.replace(Some(BUDGET)),
+    };
+    poll()
+}
+
+//takes one unit of the budget of the current task. Once it is used up the task is woken
+//again and has to return Pending, so a task whose futures are always ready cannot starve the others
+pub(crate) fn poll_budget(cx: &mut Context<'_>) -> Poll<()> {
+    BUDGET_LEFT.with(|budget| match budget.get() {
+        Some(0) => {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+        Some(left) => {
+            budget.set(Some(left - 1));
+            Poll::Ready(())
+        }
+        None => Poll::Ready(()),
+    })
+}
+
+//async form of `poll_budget`, for loops that only await once they run out of ready work
+pub(crate) async fn consume_budget() {
+    poll_fn(poll_budget).await
+}
+
+impl Future for YieldNow {
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.yielded {
+            return Poll::Ready(());
+        }
+        self.get_mut().yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+impl Drop for BudgetGuard {
+    fn drop(&mut self) {
+        BUDGET_LEFT.set(self.previous);
+    }
+}
@@ -0,0 +1,271 @@
+use crate::engine::JoinHandle;
+use crate::slab::{Key, Slab};
+use crate::task;
+use crossbeam::queue::SegQueue;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Wake, Waker},
+};
+
+/// Index of a task in its task store
+pub(crate) type TaskId = Key;
+
+/// Woken tasks waiting to be polled, each at most once
+pub(crate) type ReadyQueue = SegQueue<Arc<FutureWaker>>;
+
+/// Tasks of a reactor or local set, with the queues of woken and cancelled ones.
+/// `F` is the boxed future type, `Send` for the reactor and not for a local set
+pub struct TaskStore<F: ?Sized> {
+    tasks: Mutex<Slab<Task<F>>>,
+    ready_queue: Arc<ReadyQueue>,
+    //tasks to drop on the next turn, a task may be cancelled while another one is being polled.
+    //JoinHandles are Send, so cancellations may come from any thread
+    cancel_queue: Arc<SegQueue<TaskId>>,
+}
+
+struct Task<F: ?Sized> {
+    //taken out while polled, so the task can spawn others without the store being locked
+    task: Option<Pin<Box<F>>>,
+    waker: Arc<FutureWaker>,
+}
+
+#[derive(Debug)]
+pub(crate) struct FutureWaker {
+    id: TaskId,
+    //set while the task sits in the ready queue, so repeated wakes queue it only once
+    scheduled: AtomicBool,
+    //set once the task left its store, queued entries are then skipped without a lookup
+    completed: AtomicBool,
+    //weak since queued wakers would otherwise keep their own queue alive
+    ready_queue: Weak<ReadyQueue>,
+}
+
+impl<F: ?Sized> Default for TaskStore<F> {
+    fn default() -> Self {
+        Self {
+            tasks: Mutex::new(Slab::default()),
+            ready_queue: Arc::default(),
+            cancel_queue: Arc::default(),
+        }
+    }
+}
+
+impl<F: Future<Output = ()> + ?Sized> TaskStore<F> {
+    //adds the task built by `spawn` from its id and the cancel queue, it is polled on the next turn
+    pub(crate) fn push<K>(
+        &self,
+        spawn: impl FnOnce(TaskId, &Arc<SegQueue<TaskId>>) -> (Pin<Box<F>>, JoinHandle<K>),
+    ) -> JoinHandle<K> {
+        let mut tasks = self.tasks.lock().unwrap();
+        let id = tasks.vacant_key();
+        let (task, join_handle) = spawn(id, &self.cancel_queue);
+        let waker = FutureWaker::new(id, &self.ready_queue);
+        tasks.insert(Task {
+            task: Some(task),
+            waker: waker.clone(),
+        });
+        drop(tasks);
+        waker.schedule();
+        join_handle
+    }
+
+    //drops the tasks cancelled since the last call
+    pub(crate) fn drain_cancelled(&self) {
+        while let Some(id) = self.cancel_queue.pop() {
+            //dropped outside of the lock since the drop glue calls back into the runtime
+            let task = self.tasks.lock().unwrap().remove(id);
+            drop(task);
+        }
+    }
+
+    pub(crate) fn has_ready(&self) -> bool {
+        !self.ready_queue.is_empty()
+    }
+
+    pub(crate) fn pop_ready(&self) -> Option<Arc<FutureWaker>> {
+        self.ready_queue.pop()
+    }
+
+    //polls the task if it is still alive, entries of finished or cancelled tasks are skipped
+    pub(crate) fn poll_task(&self, waker: Arc<FutureWaker>) {
+        //stale entries are dropped here without touching the store
+        if !waker.unschedule() {
+            return;
+        }
+        let id = waker.id;
+        let Some(mut task) = self
+            .tasks
+            .lock()
+            .unwrap()
+            .get_mut(id)
+            .and_then(|task_ref| task_ref.task.take())
+        else {
+            return;
+        };
+        let waker = Waker::from(waker);
+        let mut context = Context::from_waker(&waker);
+        let polling_state = task::with_budget(|| task.as_mut().poll(&mut context));
+        let mut tasks = self.tasks.lock().unwrap();
+        if polling_state.is_pending() {
+            if let Some(task_ref) = tasks.get_mut(id) {
+                task_ref.task = Some(task);
+                return;
+            }
+        }
+        let finished = tasks.remove(id);
+        drop(tasks);
+        drop(finished);
+        drop(task);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.tasks.lock().unwrap().is_empty()
+    }
+
+    //drops every task that is still alive, releasing their timers and pollables
+    pub(crate) fn shutdown(&self) {
+        //the drop glue of a task may spawn others, so repeat until nothing is left
+        loop {
+            self.drain_cancelled();
+            let tasks = self.tasks.lock().unwrap().drain();
+            if tasks.is_empty() {
+                break;
+            }
+            drop(tasks);
+        }
+        while self.cancel_queue.pop().is_some() {}
+        while self.ready_queue.pop().is_some() {}
+    }
+}
+
+impl<F: ?Sized> Drop for Task<F> {
+    fn drop(&mut self) {
+        self.waker.complete();
+    }
+}
+
+impl FutureWaker {
+    fn new(id: TaskId, ready_queue: &Arc<ReadyQueue>) -> Arc<Self> {
+        Arc::new(Self {
+            id,
+            scheduled: AtomicBool::new(false),
+            completed: AtomicBool::new(false),
+            ready_queue: Arc::downgrade(ready_queue),
+        })
+    }
+
+    //queues the task unless it is already queued or done
+    fn schedule(self: &Arc<Self>) {
+        if self.completed.load(Ordering::Acquire) || self.scheduled.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        if let Some(ready_queue) = self.ready_queue.upgrade() {
+            ready_queue.push(self.clone());
+        }
+    }
+
+    //called once the task is taken off the ready queue, returns whether it should be polled.
+    //Wakes from here on queue the task again
+    fn unschedule(&self) -> bool {
+        self.scheduled.store(false, Ordering::Release);
+        !self.completed.load(Ordering::Acquire)
+    }
+
+    fn complete(&self) {
+        self.completed.store(true, Ordering::Release);
+    }
+}
+
+impl Wake for FutureWaker {
+    fn wake(self: Arc<Self>) {
+        self.schedule();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.schedule();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::engine::join_pair;
+    use std::task::Poll;
+
+    type SendFuture = dyn Future<Output = ()> + Send;
+
+    struct CountFuture {
+        min: u8,
+        max: u8,
+    }
+
+    impl Future for CountFuture {
+        type Output = u8;
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let count_fut_mut = self.get_mut();
+            if count_fut_mut.min == count_fut_mut.max {
+                return Poll::Ready(count_fut_mut.min);
+            }
+
+            count_fut_mut.min += 1;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    fn push(store: &TaskStore<SendFuture>, future: CountFuture) -> JoinHandle<u8> {
+        store.push(|id, cancel_queue| {
+            let (task, join_handle) = join_pair(id, future, cancel_queue, false);
+            (Box::pin(task), join_handle)
+        })
+    }
+
+    #[test]
+    fn test_enqueue() {
+        let store = TaskStore::<SendFuture>::default();
+        let handle = push(&store, CountFuture { max: 3, min: 0 });
+        while let Some(waker) = store.pop_ready() {
+            store.poll_task(waker);
+        }
+        assert!(store.is_empty());
+        assert!(handle.is_finished());
+    }
+
+    #[test]
+    fn test_duplicate_wakes_are_coalesced() {
+        let store = TaskStore::<SendFuture>::default();
+        let handle = push(&store, CountFuture { max: 1, min: 0 });
+        //pushing queued the task, which stays scheduled until it is polled
+        let queued = store.pop_ready().unwrap();
+        let waker = Waker::from(queued.clone());
+        waker.wake_by_ref();
+        waker.wake_by_ref();
+        assert!(!store.has_ready());
+
+        //CountFuture wakes itself while being polled, which queues it exactly once more
+        store.poll_task(queued);
+        assert_eq!(store.ready_queue.len(), 1);
+        store.poll_task(store.pop_ready().unwrap());
+        assert!(store.is_empty());
+        assert!(handle.is_finished());
+
+        //wakes of a finished task are dropped on the spot
+        waker.wake_by_ref();
+        assert!(!store.has_ready());
+    }
+
+    #[test]
+    fn test_cancelled_tasks_are_dropped() {
+        let store = TaskStore::<SendFuture>::default();
+        let handle = push(&store, CountFuture { max: 10, min: 0 });
+        handle.cancel();
+        store.drain_cancelled();
+        assert!(store.is_empty());
+        //the entry queued by push is skipped
+        store.poll_task(store.pop_ready().unwrap());
+        assert!(handle.is_finished());
+    }
+}
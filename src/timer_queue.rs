@@ -0,0 +1,166 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::task::Waker;
+use std::time::Instant;
+
+//stale heap entries tolerated before the heap is rebuilt from the live timers
+const STALE_SLACK: usize = 64;
+
+/// Handle of a timer registered with the reactor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TimerId(u64);
+
+/// Timers ordered by deadline. Cancelling or moving a timer only touches the map,
+/// the heap entry it leaves behind is skipped once it reaches the top
+#[derive(Debug, Default)]
+pub struct TimerQueue {
+    timers: HashMap<TimerId, TimerEntry>,
+    heap: BinaryHeap<Reverse<(Instant, TimerId)>>,
+    next_id: u64,
+}
+
+#[derive(Debug)]
+struct TimerEntry {
+    deadline: Instant,
+    waker: Waker,
+}
+
+impl TimerQueue {
+    //registers a timer, or refreshes the one behind `id`. Returns the id to use from now on
+    pub(crate) fn insert(
+        &mut self,
+        id: Option<TimerId>,
+        deadline: Instant,
+        waker: Waker,
+    ) -> TimerId {
+        let id = id.unwrap_or_else(|| {
+            self.next_id += 1;
+            TimerId(self.next_id)
+        });
+        match self.timers.get_mut(&id) {
+            //polled again with the same deadline, only the waker may have changed
+            Some(entry) if entry.deadline == deadline => {
+                if !entry.waker.will_wake(&waker) {
+                    entry.waker = waker;
+                }
+            }
+            _ => {
+                self.timers.insert(id, TimerEntry { deadline, waker });
+                self.push(deadline, id);
+            }
+        }
+        id
+    }
+
+    //moves the deadline of a registered timer, keeping its waker
+    pub(crate) fn reset(&mut self, id: TimerId, deadline: Instant) {
+        let Some(entry) = self.timers.get_mut(&id) else {
+            return;
+        };
+        if entry.deadline != deadline {
+            entry.deadline = deadline;
+            self.push(deadline, id);
+        }
+    }
+
+    pub(crate) fn remove(&mut self, id: TimerId) {
+        self.timers.remove(&id);
+    }
+
+    pub(crate) fn contains(&self, id: TimerId) -> bool {
+        self.timers.contains_key(&id)
+    }
+
+    //removes every timer due at `now`, returning their wakers. Timers further out are not visited
+    pub(crate) fn expire(&mut self, now: Instant) -> Vec<Waker> {
+        let mut wakers = Vec::new();
+        while let Some(&Reverse((deadline, id))) = self.heap.peek() {
+            if deadline > now {
+                break;
+            }
+            self.heap.pop();
+            if self.is_live(deadline, id) {
+                wakers.extend(self.timers.remove(&id).map(|entry| entry.waker));
+            }
+        }
+        wakers
+    }
+
+    pub(crate) fn next_deadline(&mut self) -> Option<Instant> {
+        while let Some(&Reverse((deadline, id))) = self.heap.peek() {
+            if self.is_live(deadline, id) {
+                return Some(deadline);
+            }
+            self.heap.pop();
+        }
+        None
+    }
+
+    #[cfg(test)]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.timers.len()
+    }
+
+    fn is_live(&self, deadline: Instant, id: TimerId) -> bool {
+        self.timers
+            .get(&id)
+            .is_some_and(|entry| entry.deadline == deadline)
+    }
+
+    fn push(&mut self, deadline: Instant, id: TimerId) {
+        self.heap.push(Reverse((deadline, id)));
+        //keeps a churn of cancelled timers from growing the heap without bound
+        if self.heap.len() > 2 * self.timers.len() + STALE_SLACK {
+            self.heap = self
+                .timers
+                .iter()
+                .map(|(id, entry)| Reverse((entry.deadline, *id)))
+                .collect();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TimerQueue;
+    use std::task::Waker;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_expire_only_visits_due_timers() {
+        let mut queue = TimerQueue::default();
+        let now = Instant::now();
+        let late = queue.insert(None, now + Duration::from_secs(10), Waker::noop().clone());
+        let early = queue.insert(None, now + Duration::from_secs(1), Waker::noop().clone());
+        let cancelled = queue.insert(None, now, Waker::noop().clone());
+        queue.remove(cancelled);
+        assert_eq!(queue.next_deadline(), Some(now + Duration::from_secs(1)));
+
+        assert_eq!(queue.expire(now + Duration::from_secs(2)).len(), 1);
+        assert!(!queue.contains(early));
+        assert!(queue.contains(late));
+
+        //moved forward, the old heap entry is skipped
+        queue.reset(late, now + Duration::from_secs(3));
+        assert_eq!(queue.next_deadline(), Some(now + Duration::from_secs(3)));
+        assert_eq!(queue.expire(now + Duration::from_secs(20)).len(), 1);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_cancelled_timers_do_not_grow_the_heap() {
+        let mut queue = TimerQueue::default();
+        let now = Instant::now();
+        for i in 0..10_000 {
+            let id = queue.insert(None, now + Duration::from_millis(i), Waker::noop().clone());
+            queue.remove(id);
+        }
+        assert_eq!(queue.len(), 0);
+        assert!(queue.heap.len() <= super::STALE_SLACK + 1);
+    }
+}
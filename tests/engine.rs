@@ -1,7 +1,14 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::cell::RefCell;
+use std::net::IpAddr;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
-use tiny_wasm_runtime::{Timer, WasmRuntimeAsyncEngine};
+use std::time::{Duration, Instant};
+use tiny_wasm_runtime::io::net::{TcpListener, TcpStream};
+use tiny_wasm_runtime::task::yield_now;
+use tiny_wasm_runtime::{
+    spawn_local, Handle, LocalSet, PanicPolicy, Runtime, Timer, WasmRuntimeAsyncEngine,
+};
 
 #[test]
 fn test_block_on_return_value() {
@@ -23,7 +30,7 @@ fn test_block_on_return_value() {
         println!("wait for result");
 
         // Await the spawned task
-        let spawned_result = handle.await;
+        let spawned_result = handle.await.expect("spawned task failed");
         println!("[Main] Spawned task returned: {spawned_result}");
 
         // Compose a result
@@ -36,3 +43,328 @@ fn test_block_on_return_value() {
 
     assert_eq!(result, 1000, "The final result should be 1000");
 }
+
+#[test]
+fn test_runtime_instance_block_on() {
+    let runtime = Runtime::new();
+    let result = runtime.block_on(async {
+        let handle = Handle::current().spawn(async {
+            Timer::sleep(Duration::from_millis(50)).await;
+            41
+        });
+        handle.await.expect("spawned task failed") + 1
+    });
+    assert_eq!(result, 42);
+
+    // the same runtime can be reused once the previous call has returned
+    let result = runtime.block_on(async { 7 });
+    assert_eq!(result, 7);
+}
+
+#[test]
+fn test_stale_handle_does_not_cancel_new_task() {
+    let runtime = Runtime::new();
+    // the task is still pending when block_on returns, so shutdown drops it
+    #[allow(clippy::async_yields_async)]
+    let stale = runtime.block_on(async {
+        WasmRuntimeAsyncEngine::spawn(async {
+            Timer::sleep(Duration::from_secs(10)).await;
+        })
+    });
+
+    let result = runtime.block_on(async move {
+        // the new task reuses the slot of the dropped one
+        let fresh = WasmRuntimeAsyncEngine::spawn(async {
+            Timer::sleep(Duration::from_millis(20)).await;
+            "fresh"
+        });
+        stale.cancel();
+        fresh.await
+    });
+    assert_eq!(result.expect("new task was cancelled"), "fresh");
+}
+
+#[test]
+fn test_cancelled_task_returns_join_error() {
+    WasmRuntimeAsyncEngine::block_on(async {
+        let handle = WasmRuntimeAsyncEngine::spawn(async {
+            Timer::sleep(Duration::from_millis(100)).await;
+            "never"
+        });
+        assert!(!handle.is_finished());
+
+        handle.cancel();
+        let err = handle
+            .await
+            .expect_err("a cancelled task should not return");
+        assert!(err.is_cancelled());
+        assert!(!err.is_panic());
+
+        let handle = WasmRuntimeAsyncEngine::spawn(async { 5 });
+        Timer::sleep(Duration::from_millis(10)).await;
+        assert!(handle.is_finished());
+        assert_eq!(handle.await.expect("task should complete"), 5);
+    });
+}
+
+#[test]
+fn test_panicking_task_is_isolated() {
+    let runtime = Runtime::new();
+    runtime.block_on(async {
+        let handle = WasmRuntimeAsyncEngine::spawn(async {
+            panic!("boom");
+        });
+        let sibling = WasmRuntimeAsyncEngine::spawn(async {
+            Timer::sleep(Duration::from_millis(20)).await;
+            "still running"
+        });
+
+        let err = handle
+            .await
+            .expect_err("a panicking task should not return");
+        assert!(err.is_panic());
+        let payload = err.into_panic();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"boom"));
+        assert_eq!(sibling.await.expect("sibling task failed"), "still running");
+    });
+
+    // the runtime is still usable after a task panicked
+    assert_eq!(runtime.block_on(async { 1 }), 1);
+}
+
+#[test]
+fn test_panic_hook_is_called() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let hook_calls = calls.clone();
+    let runtime = Runtime::with_panic_policy(PanicPolicy::Hook(Arc::new(move |_payload| {
+        hook_calls.fetch_add(1, Ordering::SeqCst);
+    })));
+    runtime.block_on(async {
+        let handle = WasmRuntimeAsyncEngine::spawn(async {
+            panic!("hooked");
+        });
+        assert!(handle.await.unwrap_err().is_panic());
+    });
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+#[should_panic(expected = "runtime is configured to abort")]
+fn test_abort_policy_stops_runtime() {
+    let runtime = Runtime::with_panic_policy(PanicPolicy::Abort);
+    runtime.block_on(async {
+        let _handle = WasmRuntimeAsyncEngine::spawn(async {
+            panic!("fatal");
+        });
+        Timer::sleep(Duration::from_millis(50)).await;
+    });
+}
+
+#[test]
+fn test_runtime_is_reusable_after_abort() {
+    let runtime = Runtime::with_panic_policy(PanicPolicy::Abort);
+    let dropped = Arc::new(AtomicBool::new(false));
+    let pending = dropped.clone();
+    let aborted = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        runtime.block_on(async move {
+            WasmRuntimeAsyncEngine::spawn(async move {
+                // only dropped by the shutdown, it never gets to finish
+                let _flag = DropFlag(pending);
+                Timer::sleep(Duration::from_secs(10)).await;
+            });
+            let _handle = WasmRuntimeAsyncEngine::spawn(async {
+                panic!("fatal");
+            });
+            Timer::sleep(Duration::from_millis(50)).await;
+        })
+    }));
+    assert!(aborted.is_err());
+    assert!(dropped.load(Ordering::SeqCst), "the pending task leaked");
+
+    // the abort request does not carry over into the next run
+    assert_eq!(runtime.block_on(async { 3 }), 3);
+}
+
+#[test]
+fn test_block_on_returns_with_background_tasks() {
+    let start = Instant::now();
+    let result = WasmRuntimeAsyncEngine::block_on(async {
+        // a heartbeat that never finishes on its own
+        WasmRuntimeAsyncEngine::spawn(async {
+            loop {
+                Timer::sleep(Duration::from_millis(10)).await;
+            }
+        });
+        Timer::sleep(Duration::from_millis(30)).await;
+        "root done"
+    });
+    assert_eq!(result, "root done");
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn test_block_on_all_waits_for_spawned_tasks() {
+    let done = Arc::new(AtomicBool::new(false));
+    let task_done = done.clone();
+    WasmRuntimeAsyncEngine::block_on_all(async move {
+        WasmRuntimeAsyncEngine::spawn(async move {
+            Timer::sleep(Duration::from_millis(50)).await;
+            task_done.store(true, Ordering::SeqCst);
+        });
+    });
+    assert!(done.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_block_on_with_shutdown_timeout() {
+    let done = Arc::new(AtomicBool::new(false));
+    let task_done = done.clone();
+    let start = Instant::now();
+    Runtime::new().block_on_with_shutdown_timeout(
+        async move {
+            WasmRuntimeAsyncEngine::spawn(async move {
+                Timer::sleep(Duration::from_millis(20)).await;
+                task_done.store(true, Ordering::SeqCst);
+            });
+            WasmRuntimeAsyncEngine::spawn(async {
+                Timer::sleep(Duration::from_secs(10)).await;
+            });
+        },
+        Duration::from_millis(100),
+    );
+    assert!(done.load(Ordering::SeqCst));
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn test_spawn_local_with_non_send_state() {
+    let runtime = Runtime::new();
+    let total = runtime.block_on_local(async {
+        let counter = Rc::new(RefCell::new(0));
+        let handles = (0..3)
+            .map(|_| {
+                let counter = counter.clone();
+                spawn_local(async move {
+                    Timer::sleep(Duration::from_millis(10)).await;
+                    *counter.borrow_mut() += 1;
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.await.expect("local task failed");
+        }
+        // Send tasks still run next to the local ones
+        let sent = WasmRuntimeAsyncEngine::spawn(async { 10 }).await.unwrap();
+        let total = *counter.borrow() + sent;
+        total
+    });
+    assert_eq!(total, 13);
+}
+
+#[test]
+fn test_local_set_spawn_before_block_on() {
+    let local = LocalSet::new();
+    let shared = Rc::new(RefCell::new(Vec::new()));
+    let task_shared = shared.clone();
+    let early = local.spawn_local(async move {
+        task_shared.borrow_mut().push("early");
+    });
+    let cancelled = local.spawn_local(async {
+        Timer::sleep(Duration::from_secs(10)).await;
+    });
+    local.block_on(&Runtime::new(), async move {
+        early.await.expect("early task failed");
+        cancelled.cancel();
+        assert!(cancelled.await.unwrap_err().is_cancelled());
+    });
+    assert_eq!(*shared.borrow(), vec!["early"]);
+}
+
+#[test]
+fn test_yield_now_interleaves_tasks() {
+    let order = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let task_order = order.clone();
+    WasmRuntimeAsyncEngine::block_on(async move {
+        let handles = ["a", "b"]
+            .into_iter()
+            .map(|name| {
+                let order = task_order.clone();
+                WasmRuntimeAsyncEngine::spawn(async move {
+                    for _ in 0..3 {
+                        order.lock().unwrap().push(name);
+                        yield_now().await;
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.await.expect("task failed");
+        }
+    });
+    assert_eq!(*order.lock().unwrap(), ["a", "b", "a", "b", "a", "b"]);
+}
+
+#[test]
+fn test_always_ready_task_does_not_starve_others() {
+    let spins = Arc::new(AtomicUsize::new(0));
+    let task_spins = spins.clone();
+    WasmRuntimeAsyncEngine::block_on(async move {
+        // never waits on anything, so only the budget makes it give up the thread
+        WasmRuntimeAsyncEngine::spawn(async move {
+            loop {
+                Timer::sleep(Duration::ZERO).await;
+                task_spins.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        Timer::sleep(Duration::from_millis(20)).await;
+    });
+    assert!(spins.load(Ordering::SeqCst) > 0);
+}
+
+#[test]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    ignore = "needs a wasi:sockets host such as wasmtime"
+)]
+fn test_ready_socket_reader_does_not_starve_others() {
+    WasmRuntimeAsyncEngine::block_on(async {
+        let addr = IpAddr::from([127, 0, 0, 1]);
+        let listener = TcpListener::bind((addr, 63009))
+            .await
+            .expect("Failed to bind listener");
+        let server = WasmRuntimeAsyncEngine::spawn(async move {
+            let (mut stream, _peer) = listener.accept().await.expect("accept failed");
+            stream.write_all(&[7; 4096]).await.expect("write failed");
+            stream.flush().await.expect("flush failed");
+            // keeps the connection open until the client is done reading
+            Timer::sleep(Duration::from_millis(500)).await;
+        });
+        let mut stream = TcpStream::new_ipv4().expect("Failed to create TCP stream");
+        stream.connect((addr, 63009)).await.expect("connect failed");
+        // lets all of the data arrive, so every read below is ready right away
+        Timer::sleep(Duration::from_millis(100)).await;
+
+        let other_ran = Arc::new(AtomicBool::new(false));
+        let flag = other_ran.clone();
+        WasmRuntimeAsyncEngine::spawn(async move { flag.store(true, Ordering::SeqCst) });
+        let mut reads_before_other = None;
+        let mut byte = [0u8; 1];
+        for read in 0..4096 {
+            stream.read_exact(&mut byte).await.expect("read failed");
+            if reads_before_other.is_none() && other_ran.load(Ordering::SeqCst) {
+                reads_before_other = Some(read);
+            }
+        }
+        // only the budget charged by the reads lets the other task in
+        assert!(reads_before_other.is_some_and(|reads| reads < 4096));
+        server.await.expect("server task failed");
+    });
+}
+
+struct DropFlag(Arc<AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
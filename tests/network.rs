@@ -1,43 +1,73 @@
-use std::io::{Read, Write};
-use std::net::{IpAddr, TcpListener};
+#[cfg(feature = "futures-io")]
+use futures::io::{AsyncReadExt, AsyncWriteExt};
+use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
-use std::thread;
-use std::time::Duration;
-use tiny_wasm_runtime::io::net::TcpStream;
+use std::time::{Duration, Instant};
+use tiny_wasm_runtime::bindings::wasi::clocks::monotonic_clock;
+use tiny_wasm_runtime::bindings::wasi::sockets::network::ErrorCode;
+use tiny_wasm_runtime::io::net::{
+    lookup_host, TcpListener, TcpSocketBuilder, TcpStream, UdpSocket,
+};
+use tiny_wasm_runtime::io::wait_for;
 use tiny_wasm_runtime::{Timer, WasmRuntimeAsyncEngine};
 
 #[test]
-#[ignore = "first need support for tcp listening"]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    ignore = "needs a wasi:sockets host such as wasmtime"
+)]
 fn test_tcp_stream_connect() {
     WasmRuntimeAsyncEngine::block_on(async {
         println!("=== TcpStream Connect Test Start ===");
 
+        let addr = IpAddr::from_str("127.0.0.1").expect("Invalid IP address");
+        let listener = TcpListener::bind((addr, 63000))
+            .await
+            .expect("Failed to bind listener");
+
+        println!("[Main] Listening on 127.0.0.1:63000.");
+
+        // Echo a single message back to the client
+        let server = WasmRuntimeAsyncEngine::spawn(async move {
+            let (mut stream, _peer) = listener.accept().await.expect("accept failed");
+            let mut buf = [0u8; 4];
+            stream
+                .read_exact(&mut buf)
+                .await
+                .expect("server read failed");
+            stream.write_all(&buf).await.expect("server write failed");
+            stream.flush().await.expect("server flush failed");
+        });
+
         // Create the TCP stream
         let mut stream = TcpStream::new_ipv4().expect("Failed to create TCP stream");
 
         println!("[Main] Created TcpStream.");
 
-        // Attempt to connect to localhost:8080
-        let addr = IpAddr::from_str("127.0.0.1").expect("Invalid IP address");
-
-        let connect_result = stream.connect(addr, 63000).await;
+        let connect_result = stream.connect((addr, 63000)).await;
 
         match connect_result {
             Ok(_) => {
                 println!("[Main] Successfully connected to 127.0.0.1:63000.");
             }
             Err(e) => {
-                println!("[Main] Connection failed: {:?}", e);
-                // For testing, you might still consider this a pass if the connection is refused.
-                // Uncomment below if you want it to tolerate refused connections as "success."
-                // if e.kind() == std::io::ErrorKind::ConnectionRefused {
-                //     println!("[Main] Connection refused as expected for test.");
-                //     return;
-                // }
                 panic!("Connection attempt failed unexpectedly: {:?}", e);
             }
         }
 
+        stream
+            .write_all(b"ping")
+            .await
+            .expect("client write failed");
+        stream.flush().await.expect("client flush failed");
+        let mut reply = [0u8; 4];
+        stream
+            .read_exact(&mut reply)
+            .await
+            .expect("client read failed");
+        assert_eq!(&reply, b"ping");
+        server.await.expect("server task failed");
+
         // Wait a little to simulate doing work
         Timer::sleep(Duration::from_millis(200)).await;
         println!("[Main] Done waiting, TcpStream will now be dropped.");
@@ -45,3 +75,263 @@ fn test_tcp_stream_connect() {
         println!("=== TcpStream Connect Test Complete ===");
     });
 }
+
+#[test]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    ignore = "needs a wasi:sockets host such as wasmtime"
+)]
+fn test_udp_socket_send_recv() {
+    WasmRuntimeAsyncEngine::block_on(async {
+        let addr = IpAddr::from_str("127.0.0.1").expect("Invalid IP address");
+        let server = UdpSocket::bind((addr, 63001))
+            .await
+            .expect("Failed to bind server socket");
+        let mut client = UdpSocket::bind((addr, 63002))
+            .await
+            .expect("Failed to bind client socket");
+        client.connect((addr, 63001)).expect("Failed to connect");
+
+        client.send(b"ping").await.expect("client send failed");
+
+        let mut buf = [0u8; 16];
+        let (len, _peer) = server.recv_from(&mut buf).await.expect("recv failed");
+        assert_eq!(&buf[..len], b"ping");
+
+        server
+            .send_to(&buf[..len], (addr, 63002))
+            .await
+            .expect("server send failed");
+        let len = client.recv(&mut buf).await.expect("client recv failed");
+        assert_eq!(&buf[..len], b"ping");
+    });
+}
+
+#[test]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    ignore = "needs a wasi:sockets host such as wasmtime"
+)]
+fn test_lookup_host() {
+    WasmRuntimeAsyncEngine::block_on(async {
+        let addresses = lookup_host("localhost").await.expect("lookup failed");
+        assert!(!addresses.is_empty(), "localhost should resolve");
+
+        let err = lookup_host("does-not-exist.invalid")
+            .await
+            .expect_err("lookup of an invalid name should fail");
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+        // the wasi error code stays available as the source
+        let code = err
+            .get_ref()
+            .and_then(|source| source.downcast_ref::<ErrorCode>());
+        assert!(matches!(code, Some(ErrorCode::NameUnresolvable)));
+    });
+}
+
+#[test]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    ignore = "needs a wasi host such as wasmtime"
+)]
+fn test_wait_for_clock_pollable() {
+    WasmRuntimeAsyncEngine::block_on(async {
+        let start = Instant::now();
+        // any pollable works, a clock subscription is the simplest one to get
+        let pollable = monotonic_clock::subscribe_duration(50_000_000);
+        let waiting = wait_for(pollable);
+        assert!(!waiting.pollable().ready());
+        waiting.await;
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    });
+}
+
+#[test]
+#[cfg(feature = "futures-io")]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    ignore = "needs a wasi:sockets host such as wasmtime"
+)]
+fn test_tcp_stream_futures_io() {
+    WasmRuntimeAsyncEngine::block_on(async {
+        let addr = IpAddr::from_str("127.0.0.1").expect("Invalid IP address");
+        let listener = TcpListener::bind((addr, 63003))
+            .await
+            .expect("Failed to bind listener");
+
+        // Echo everything back until the client closes its sending side
+        let server = WasmRuntimeAsyncEngine::spawn(async move {
+            let (stream, _peer) = listener.accept().await.expect("accept failed");
+            let (mut reader, mut writer) = AsyncReadExt::split(stream);
+            futures::io::copy(&mut reader, &mut writer)
+                .await
+                .expect("server copy failed");
+            writer.close().await.expect("server close failed");
+        });
+
+        let mut stream = TcpStream::new_ipv4().expect("Failed to create TCP stream");
+        stream.connect((addr, 63003)).await.expect("connect failed");
+        AsyncWriteExt::write_all(&mut stream, b"hello futures-io")
+            .await
+            .expect("client write failed");
+        stream.close().await.expect("client close failed");
+
+        let mut reply = Vec::new();
+        stream
+            .read_to_end(&mut reply)
+            .await
+            .expect("client read failed");
+        assert_eq!(reply, b"hello futures-io");
+        server.await.expect("server task failed");
+    });
+}
+
+#[test]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    ignore = "needs a wasi:sockets host such as wasmtime"
+)]
+fn test_tcp_stream_split() {
+    WasmRuntimeAsyncEngine::block_on(async {
+        let addr = IpAddr::from_str("127.0.0.1").expect("Invalid IP address");
+        let listener = TcpListener::bind((addr, 63004))
+            .await
+            .expect("Failed to bind listener");
+
+        // Reading and writing happen in separate tasks on the server side
+        let server = WasmRuntimeAsyncEngine::spawn(async move {
+            let (stream, _peer) = listener.accept().await.expect("accept failed");
+            let (mut read, mut write) = stream.into_split();
+            let echo = WasmRuntimeAsyncEngine::spawn(async move {
+                let mut buf = [0u8; 5];
+                read.read_exact(&mut buf).await.expect("server read failed");
+                read
+            });
+            write
+                .write_all(b"hello")
+                .await
+                .expect("server write failed");
+            write.flush().await.expect("server flush failed");
+            let read = echo.await.expect("echo task failed");
+            // the halves still share the socket, so they can be put back together
+            read.reunite(write)
+                .expect("halves are from the same stream");
+        });
+
+        let mut stream = TcpStream::new_ipv4().expect("Failed to create TCP stream");
+        stream.connect((addr, 63004)).await.expect("connect failed");
+        let (mut read, mut write) = stream.split();
+        write
+            .write_all(b"world")
+            .await
+            .expect("client write failed");
+        write.flush().await.expect("client flush failed");
+        let mut reply = [0u8; 5];
+        read.read_exact(&mut reply)
+            .await
+            .expect("client read failed");
+        assert_eq!(&reply, b"hello");
+        server.await.expect("server task failed");
+    });
+}
+
+#[test]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    ignore = "needs a wasi:sockets host such as wasmtime"
+)]
+fn test_tcp_socket_options() {
+    WasmRuntimeAsyncEngine::block_on(async {
+        let addr = IpAddr::from_str("127.0.0.1").expect("Invalid IP address");
+        let listener = TcpSocketBuilder::new()
+            .listen_backlog_size(16)
+            .listen((addr, 63005))
+            .await
+            .expect("Failed to listen");
+        let server = WasmRuntimeAsyncEngine::spawn(async move {
+            listener.accept().await.expect("accept failed");
+        });
+
+        let stream = TcpSocketBuilder::new()
+            .keep_alive(true)
+            .keep_alive_idle_time(Duration::from_secs(30))
+            .keep_alive_interval(Duration::from_secs(5))
+            .keep_alive_count(3)
+            .connect((addr, 63005))
+            .await
+            .expect("connect failed");
+        assert!(stream.keep_alive().expect("keep_alive failed"));
+        assert_eq!(
+            stream.keep_alive_count().expect("keep_alive_count failed"),
+            3
+        );
+
+        stream
+            .set_keep_alive_interval(Duration::from_secs(10))
+            .expect("set_keep_alive_interval failed");
+        assert_eq!(
+            stream
+                .keep_alive_interval()
+                .expect("keep_alive_interval failed"),
+            Duration::from_secs(10)
+        );
+        server.await.expect("server task failed");
+    });
+}
+
+#[test]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    ignore = "needs a wasi:sockets host such as wasmtime"
+)]
+fn test_tcp_stream_socket_addr() {
+    WasmRuntimeAsyncEngine::block_on(async {
+        let addr: SocketAddr = "127.0.0.1:63006".parse().unwrap();
+        let listener = TcpListener::bind(addr)
+            .await
+            .expect("Failed to bind listener");
+        assert_eq!(listener.local_addr().expect("local_addr failed"), addr);
+        let server = WasmRuntimeAsyncEngine::spawn(async move {
+            let (stream, peer) = listener.accept().await.expect("accept failed");
+            assert_eq!(stream.peer_addr().expect("peer_addr failed"), peer);
+            peer
+        });
+
+        let mut stream = TcpStream::new_ipv4().expect("Failed to create TCP stream");
+        stream.connect(addr).await.expect("connect failed");
+        assert_eq!(stream.peer_addr().expect("peer_addr failed"), addr);
+        let client = stream.local_addr().expect("local_addr failed");
+        assert_eq!(server.await.expect("server task failed"), client);
+    });
+}
+
+#[test]
+#[cfg_attr(
+    not(target_arch = "wasm32"),
+    ignore = "needs a wasi:sockets host such as wasmtime"
+)]
+fn test_tcp_stream_connect_any() {
+    WasmRuntimeAsyncEngine::block_on(async {
+        let addr: SocketAddr = "127.0.0.1:63007".parse().unwrap();
+        let listener = TcpListener::bind(addr)
+            .await
+            .expect("Failed to bind listener");
+        let server = WasmRuntimeAsyncEngine::spawn(async move {
+            listener.accept().await.expect("accept failed");
+        });
+
+        // nothing listens on the ipv6 address, so the race falls back to ipv4
+        let refused: SocketAddr = "[::1]:63008".parse().unwrap();
+        let stream = TcpStream::connect_any([addr, refused])
+            .await
+            .expect("connect_any failed");
+        assert_eq!(stream.peer_addr().expect("peer_addr failed"), addr);
+        server.await.expect("server task failed");
+
+        let error = TcpStream::connect_any([])
+            .await
+            .err()
+            .expect("no addresses");
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    });
+}
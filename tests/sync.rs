@@ -0,0 +1,255 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tiny_wasm_runtime::sync::{broadcast, mpsc, oneshot, watch, Mutex, Notify, RwLock, Semaphore};
+use tiny_wasm_runtime::{Runtime, Timer};
+
+#[test]
+fn test_mutex_guard_held_across_await() {
+    let runtime = Runtime::new();
+    let handle = runtime.handle();
+    let result = runtime.block_on(async move {
+        let counter = Arc::new(Mutex::new(0));
+        let tasks: Vec<_> = (0..5)
+            .map(|_| {
+                let counter = counter.clone();
+                handle.spawn(async move {
+                    let mut value = counter.lock().await;
+                    let read = *value;
+                    //nobody else may touch the value while we sleep
+                    Timer::sleep(Duration::from_millis(10)).await;
+                    *value = read + 1;
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.expect("task failed");
+        }
+        let value = *counter.lock().await;
+        value
+    });
+    assert_eq!(result, 5);
+}
+
+#[test]
+fn test_mutex_waiters_are_fifo() {
+    let runtime = Runtime::new();
+    let handle = runtime.handle();
+    let order = runtime.block_on(async move {
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let guard = order.lock().await;
+        let tasks: Vec<_> = (0..4)
+            .map(|i| {
+                let order = order.clone();
+                handle.spawn(async move {
+                    //stagger the tasks so they queue up in spawn order
+                    Timer::sleep(Duration::from_millis(10 * i as u64)).await;
+                    order.lock().await.push(i);
+                })
+            })
+            .collect();
+        Timer::sleep(Duration::from_millis(60)).await;
+        assert!(order.try_lock().is_none());
+        drop(guard);
+        for task in tasks {
+            task.await.expect("task failed");
+        }
+        let order = order.lock().await.clone();
+        order
+    });
+    assert_eq!(order, vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn test_rwlock_writer_excludes_readers() {
+    let runtime = Runtime::new();
+    let handle = runtime.handle();
+    runtime.block_on(async move {
+        let lock = Arc::new(RwLock::new(1));
+        let first = lock.read().await;
+        let second = lock.read().await;
+        assert_eq!(*first + *second, 2);
+
+        let writer = {
+            let lock = lock.clone();
+            handle.spawn(async move {
+                *lock.write().await = 2;
+            })
+        };
+        Timer::sleep(Duration::from_millis(10)).await;
+        //the queued writer holds back new readers
+        assert!(lock.try_read().is_none());
+        drop((first, second));
+        writer.await.expect("writer failed");
+        assert_eq!(*lock.read().await, 2);
+    });
+}
+
+#[test]
+fn test_semaphore_limits_concurrency() {
+    let runtime = Runtime::new();
+    let handle = runtime.handle();
+    let peak = runtime.block_on(async move {
+        let semaphore = Arc::new(Semaphore::new(2));
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let tasks: Vec<_> = (0..6)
+            .map(|_| {
+                let semaphore = semaphore.clone();
+                let running = running.clone();
+                let peak = peak.clone();
+                handle.spawn(async move {
+                    let _permit = semaphore.acquire_owned().await;
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    Timer::sleep(Duration::from_millis(10)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.expect("task failed");
+        }
+        assert_eq!(semaphore.available_permits(), 2);
+        peak.load(Ordering::SeqCst)
+    });
+    assert_eq!(peak, 2);
+}
+
+#[test]
+fn test_notify_one_and_waiters() {
+    let runtime = Runtime::new();
+    let handle = runtime.handle();
+    runtime.block_on(async move {
+        let notify = Arc::new(Notify::new());
+        //a notification without waiters is kept for the next one
+        notify.notify_one();
+        notify.notified().await;
+
+        let woken = Arc::new(AtomicUsize::new(0));
+        let tasks: Vec<_> = (0..3)
+            .map(|_| {
+                let notify = notify.clone();
+                let woken = woken.clone();
+                handle.spawn(async move {
+                    notify.notified().await;
+                    woken.fetch_add(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        Timer::sleep(Duration::from_millis(10)).await;
+        notify.notify_one();
+        Timer::sleep(Duration::from_millis(10)).await;
+        assert_eq!(woken.load(Ordering::SeqCst), 1);
+        notify.notify_waiters();
+        for task in tasks {
+            task.await.expect("task failed");
+        }
+        assert_eq!(woken.load(Ordering::SeqCst), 3);
+    });
+}
+
+#[test]
+fn test_bounded_mpsc_applies_backpressure() {
+    let runtime = Runtime::new();
+    let handle = runtime.handle();
+    let received = runtime.block_on(async move {
+        let (sender, mut receiver) = mpsc::channel(2);
+        let producer = handle.spawn(async move {
+            for i in 0..10 {
+                sender.send(i).await.expect("receiver dropped");
+            }
+        });
+        Timer::sleep(Duration::from_millis(10)).await;
+        //the producer is parked until we make room
+        assert!(!producer.is_finished());
+        let mut received = Vec::new();
+        while let Some(value) = receiver.recv().await {
+            received.push(value);
+        }
+        producer.await.expect("producer failed");
+        received
+    });
+    assert_eq!(received, (0..10).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_mpsc_send_fails_once_receiver_is_dropped() {
+    let runtime = Runtime::new();
+    runtime.block_on(async {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        sender.send(1).expect("receiver dropped");
+        drop(receiver);
+        assert!(sender.is_closed());
+        assert_eq!(sender.send(2).map_err(|error| error.0), Err(2));
+
+        let (sender, mut receiver) = mpsc::channel(1);
+        sender.try_send(1).expect("channel full");
+        assert_eq!(sender.try_send(2), Err(mpsc::TrySendError::Full(2)));
+        assert_eq!(receiver.try_recv(), Ok(1));
+        drop(sender);
+        assert_eq!(receiver.try_recv(), Err(mpsc::TryRecvError::Disconnected));
+    });
+}
+
+#[test]
+fn test_oneshot_delivers_value_or_error() {
+    let runtime = Runtime::new();
+    let handle = runtime.handle();
+    runtime.block_on(async move {
+        let (sender, receiver) = oneshot::channel();
+        handle.spawn(async move {
+            Timer::sleep(Duration::from_millis(10)).await;
+            sender.send("done").expect("receiver dropped");
+        });
+        assert_eq!(receiver.await, Ok("done"));
+
+        let (sender, receiver) = oneshot::channel::<()>();
+        drop(sender);
+        assert!(receiver.await.is_err());
+    });
+}
+
+#[test]
+fn test_broadcast_reports_lagging_receivers() {
+    let runtime = Runtime::new();
+    runtime.block_on(async {
+        let (sender, mut slow) = broadcast::channel(2);
+        let mut fast = sender.subscribe();
+        for i in 0..3 {
+            assert_eq!(sender.send(i).expect("no receivers"), 2);
+            assert_eq!(fast.recv().await, Ok(i));
+        }
+        //the oldest message was pushed out before the slow receiver read it
+        assert_eq!(slow.recv().await, Err(broadcast::RecvError::Lagged(1)));
+        assert_eq!(slow.recv().await, Ok(1));
+        assert_eq!(slow.recv().await, Ok(2));
+        drop(sender);
+        assert_eq!(slow.recv().await, Err(broadcast::RecvError::Closed));
+    });
+}
+
+#[test]
+fn test_watch_receiver_sees_latest_value() {
+    let runtime = Runtime::new();
+    let handle = runtime.handle();
+    runtime.block_on(async move {
+        let (sender, mut receiver) = watch::channel(0);
+        let watcher = handle.spawn(async move {
+            let mut seen = Vec::new();
+            while receiver.changed().await.is_ok() {
+                seen.push(*receiver.borrow_and_update());
+            }
+            seen
+        });
+        Timer::sleep(Duration::from_millis(10)).await;
+        sender.send(1).expect("no receivers");
+        Timer::sleep(Duration::from_millis(10)).await;
+        //both updates land before the watcher runs, so it only sees the last one
+        sender.send(2).expect("no receivers");
+        sender.send_modify(|value| *value += 1);
+        Timer::sleep(Duration::from_millis(10)).await;
+        drop(sender);
+        assert_eq!(watcher.await.expect("watcher failed"), vec![1, 3]);
+    });
+}
@@ -1,7 +1,8 @@
+use futures::StreamExt;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
-use tiny_wasm_runtime::{Timer, WasmRuntimeAsyncEngine};
+use std::time::{Duration, Instant};
+use tiny_wasm_runtime::{MissedTickBehavior, Timer, WasmRuntimeAsyncEngine};
 pub async fn test_timers_with_assertions() {
     let task_a_done: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
     let task_b_done = Arc::new(AtomicBool::new(false));
@@ -98,3 +99,107 @@ fn test_timeout_behavior() {
         println!("=== Timeout Behavior Test Complete ===");
     });
 }
+
+#[test]
+fn test_interval_does_not_drift() {
+    WasmRuntimeAsyncEngine::block_on(async {
+        let start = Instant::now();
+        let period = Duration::from_millis(20);
+        let mut interval = Timer::interval_at(start, period);
+        //ticks stay on the original schedule even when the work in between takes time
+        for i in 0..5 {
+            let tick = interval.tick().await;
+            assert_eq!(tick, start + period * i);
+            assert!(Instant::now() >= tick);
+            Timer::sleep(period / 4).await;
+        }
+    });
+}
+
+#[test]
+fn test_interval_missed_tick_behavior() {
+    WasmRuntimeAsyncEngine::block_on(async {
+        let period = Duration::from_millis(20);
+        let start = Instant::now();
+        let mut burst = Timer::interval_at(start, period);
+        let mut skip = Timer::interval_at(start, period);
+        skip.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut delay = Timer::interval_at(start, period);
+        delay.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        for interval in [&mut burst, &mut skip, &mut delay] {
+            interval.tick().await;
+        }
+
+        //miss a few ticks
+        std::thread::sleep(Duration::from_millis(70));
+        let now = Instant::now();
+        for interval in [&mut burst, &mut skip] {
+            assert_eq!(interval.tick().await, start + period);
+        }
+        assert!(delay.tick().await >= start + period);
+
+        //burst fires the missed ticks right away
+        assert_eq!(burst.tick().await, start + period * 2);
+        assert!(now.elapsed() < period);
+        //skip waits for the next tick on the original schedule
+        assert_eq!(skip.tick().await, start + period * 4);
+        //delay counts a whole period from the late tick
+        assert!(delay.tick().await >= now + period);
+    });
+}
+
+#[test]
+fn test_interval_stream_and_reset() {
+    WasmRuntimeAsyncEngine::block_on(async {
+        let period = Duration::from_millis(10);
+        let ticks: Vec<Instant> = Timer::interval(period).take(3).collect().await;
+        assert_eq!(ticks.len(), 3);
+        assert_eq!(ticks[2] - ticks[0], period * 2);
+
+        let mut interval = Timer::interval(period);
+        interval.tick().await;
+        Timer::sleep(Duration::from_millis(5)).await;
+        let reset_at = Instant::now();
+        interval.reset();
+        assert!(interval.tick().await >= reset_at + period);
+    });
+}
+
+#[test]
+fn test_sleep_until_and_reset() {
+    WasmRuntimeAsyncEngine::block_on(async {
+        let start = Instant::now();
+        let deadline = start + Duration::from_millis(20);
+        let mut sleep = Timer::sleep_until(deadline);
+        assert_eq!(sleep.deadline(), deadline);
+        assert!(!sleep.is_elapsed());
+
+        //pushed back while pending, as an idle timeout would on activity
+        let pushed_back = Timer::timeout(&mut sleep, Duration::from_millis(10)).await;
+        assert!(pushed_back.is_err());
+        sleep.reset(start + Duration::from_millis(50));
+        (&mut sleep).await;
+        assert!(sleep.is_elapsed());
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        //brought forward from a far deadline, the reactor must not wait for the old one
+        let mut sleep = Timer::sleep(Duration::from_secs(10));
+        let _ = Timer::timeout(&mut sleep, Duration::from_millis(5)).await;
+        let reset_at = Instant::now();
+        sleep.reset(reset_at + Duration::from_millis(10));
+        sleep.await;
+        assert!(reset_at.elapsed() < Duration::from_secs(1));
+    });
+}
+
+#[test]
+fn test_timeout_exposes_deadline() {
+    WasmRuntimeAsyncEngine::block_on(async {
+        let deadline = Instant::now() + Duration::from_millis(30);
+        let timeout = Timer::timeout_at(Timer::sleep(Duration::from_secs(1)), deadline);
+        assert_eq!(timeout.deadline(), deadline);
+        let err = timeout.await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+        assert!(Instant::now() >= deadline);
+    });
+}